@@ -0,0 +1,216 @@
+use proc_macro2::TokenStream;
+use syn::{Attribute, Error, Ident, Lit, Meta, MetaList, MetaNameValue, NestedMeta, Result};
+
+/// `#[sqlz(...)]` on the struct
+#[derive(Default)]
+pub(crate) struct TableAttrs {
+    pub(crate) name: Option<String>,
+    pub(crate) primary_key: Option<Vec<String>>,
+    pub(crate) unique_keys: Vec<NamedColumns>,
+    pub(crate) indexes: Vec<NamedColumns>,
+    pub(crate) comment: Option<String>,
+}
+
+/// `unique(...)` or `index(...)` with an optional name
+pub(crate) struct NamedColumns {
+    pub(crate) name: Option<String>,
+    pub(crate) columns: Vec<String>,
+    pub(crate) meta: MetaList,
+}
+
+/// `#[sqlz(...)]` on a field
+#[derive(Default)]
+pub(crate) struct FieldAttrs {
+    pub(crate) rename: Option<String>,
+    pub(crate) col_type: Option<TokenStream>,
+    pub(crate) key: Option<Ident>,
+    pub(crate) auto_increment: bool,
+    pub(crate) skip: bool,
+    pub(crate) foreign_key: Option<ForeignKeyAttr>,
+    pub(crate) comment: Option<String>,
+}
+
+/// `foreign_key(table = "...", column = "...", ...)` on a field
+pub(crate) struct ForeignKeyAttr {
+    pub(crate) name: Option<String>,
+    pub(crate) table: String,
+    pub(crate) column: String,
+    pub(crate) on_delete: Option<Ident>,
+    pub(crate) on_update: Option<Ident>,
+}
+
+const FOREIGN_KEY_ACTIONS: &[&str] = &["Restrict", "Cascade", "SetNull", "NoAction", "SetDefault"];
+
+impl TableAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut res = TableAttrs::default();
+        for meta in sqlz_metas(attrs)? {
+            match &meta {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("table") => {
+                    res.name = Some(lit_str(nv)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("comment") => {
+                    res.comment = Some(lit_str(nv)?);
+                }
+                NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("primary_key") => {
+                    res.primary_key = Some(str_list(l)?);
+                }
+                NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("unique") => {
+                    res.unique_keys.push(named_columns(l)?);
+                }
+                NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("index") => {
+                    res.indexes.push(named_columns(l)?);
+                }
+                _ => return Err(Error::new_spanned(meta, "unknown sqlz table attribute")),
+            }
+        }
+        Ok(res)
+    }
+}
+
+impl FieldAttrs {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut res = FieldAttrs::default();
+        for meta in sqlz_metas(attrs)? {
+            match &meta {
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("primary_key") => {
+                    res.key = Some(Ident::new("Primary", p.segments[0].ident.span()));
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("unique") => {
+                    res.key = Some(Ident::new("Unique", p.segments[0].ident.span()));
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("index") => {
+                    res.key = Some(Ident::new("Multiple", p.segments[0].ident.span()));
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("auto_increment") => {
+                    res.auto_increment = true;
+                }
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("skip") => {
+                    res.skip = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                    res.rename = Some(lit_str(nv)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("comment") => {
+                    res.comment = Some(lit_str(nv)?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("col_type") => {
+                    let s = lit_str(nv)?;
+                    let tokens = syn::parse_str::<TokenStream>(&s)
+                        .map_err(|e| Error::new_spanned(&nv.lit, e))?;
+                    res.col_type = Some(tokens);
+                }
+                NestedMeta::Meta(Meta::List(l)) if l.path.is_ident("foreign_key") => {
+                    res.foreign_key = Some(foreign_key(l)?);
+                }
+                _ => return Err(Error::new_spanned(meta, "unknown sqlz field attribute")),
+            }
+        }
+        Ok(res)
+    }
+}
+
+fn sqlz_metas(attrs: &[Attribute]) -> Result<Vec<NestedMeta>> {
+    let mut res = vec![];
+    for attr in attrs.iter().filter(|a| a.path.is_ident("sqlz")) {
+        match attr.parse_meta()? {
+            Meta::List(l) => res.extend(l.nested),
+            meta => return Err(Error::new_spanned(meta, "expected #[sqlz(...)]")),
+        }
+    }
+    Ok(res)
+}
+
+fn lit_str(nv: &MetaNameValue) -> Result<String> {
+    match &nv.lit {
+        Lit::Str(s) => Ok(s.value()),
+        lit => Err(Error::new_spanned(lit, "expected a string")),
+    }
+}
+
+/// `("a", "b")`
+fn str_list(l: &MetaList) -> Result<Vec<String>> {
+    let list = l
+        .nested
+        .iter()
+        .map(|n| match n {
+            NestedMeta::Lit(Lit::Str(s)) => Ok(s.value()),
+            n => Err(Error::new_spanned(n, "expected a string")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if list.is_empty() {
+        return Err(Error::new_spanned(l, "expected at least one column"));
+    }
+    Ok(list)
+}
+
+/// `(name = "...", columns("a", "b"))`
+fn named_columns(l: &MetaList) -> Result<NamedColumns> {
+    let mut name = None;
+    let mut columns = None;
+    for n in l.nested.iter() {
+        match n {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                name = Some(lit_str(nv)?);
+            }
+            NestedMeta::Meta(Meta::List(c)) if c.path.is_ident("columns") => {
+                columns = Some(str_list(c)?);
+            }
+            n => return Err(Error::new_spanned(n, "expected `name` or `columns`")),
+        }
+    }
+    let columns = columns.ok_or_else(|| Error::new_spanned(l, "missing `columns`"))?;
+    Ok(NamedColumns {
+        name,
+        columns,
+        meta: l.clone(),
+    })
+}
+
+/// `(table = "...", column = "...", name = "...", on_delete = "...", on_update = "...")`,
+/// `column` is `id` by default
+fn foreign_key(l: &MetaList) -> Result<ForeignKeyAttr> {
+    let mut name = None;
+    let mut table = None;
+    let mut column = None;
+    let mut on_delete = None;
+    let mut on_update = None;
+    for n in l.nested.iter() {
+        match n {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => {
+                name = Some(lit_str(nv)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("table") => {
+                table = Some(lit_str(nv)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("column") => {
+                column = Some(lit_str(nv)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("on_delete") => {
+                on_delete = Some(action(nv)?);
+            }
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("on_update") => {
+                on_update = Some(action(nv)?);
+            }
+            n => return Err(Error::new_spanned(n, "unknown foreign key attribute")),
+        }
+    }
+    Ok(ForeignKeyAttr {
+        name,
+        table: table.ok_or_else(|| Error::new_spanned(l, "missing `table`"))?,
+        column: column.unwrap_or_else(|| "id".to_owned()),
+        on_delete,
+        on_update,
+    })
+}
+
+fn action(nv: &MetaNameValue) -> Result<Ident> {
+    let s = lit_str(nv)?;
+    if !FOREIGN_KEY_ACTIONS.contains(&s.as_str()) {
+        return Err(Error::new_spanned(
+            &nv.lit,
+            format!("expected one of {}", FOREIGN_KEY_ACTIONS.join(", ")),
+        ));
+    }
+    Ok(Ident::new(&s, nv.lit.span()))
+}
@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, GenericArgument, PathArguments, Result, Type};
+
+/// `(column type, nullable)` of a field type, `Option<T>` is nullable.
+/// `col_type` from the attribute takes place of the mapped type.
+pub(crate) fn map(ty: &Type, col_type: Option<&TokenStream>) -> Result<(TokenStream, bool)> {
+    let (ty, nullable) = match generic_of(ty, "Option") {
+        Some(inner) => (inner, true),
+        None => (ty, false),
+    };
+    let col_type = match col_type {
+        Some(c) => quote!(::sqlz::ColumnType::#c),
+        None => column_type(ty)?,
+    };
+    Ok((col_type, nullable))
+}
+
+fn column_type(ty: &Type) -> Result<TokenStream> {
+    if let Some(inner) = generic_of(ty, "Vec") {
+        if last_ident(inner).as_deref() == Some("u8") {
+            return Ok(quote!(::sqlz::ColumnType::Binary));
+        }
+        let inner = column_type(inner)?;
+        return Ok(quote!(::sqlz::ColumnType::Array(Box::new(#inner))));
+    }
+
+    let t = match last_ident(ty).as_deref() {
+        Some("bool") => quote!(Bool),
+        Some("i8") | Some("i16") | Some("u8") => quote!(SmallInt),
+        Some("i32") | Some("u16") => quote!(Int),
+        Some("i64") | Some("u32") => quote!(BigInt),
+        Some("f32") => quote!(Float),
+        Some("f64") => quote!(Double),
+        Some("char") => quote!(Char(Some(1))),
+        Some("String") => quote!(VarChar(None)),
+        Some("Value") => quote!(Json),
+        Some("Uuid") => quote!(Uuid),
+        Some("NaiveDate") => quote!(Date),
+        Some("NaiveTime") => quote!(Time),
+        Some("NaiveDateTime") => quote!(DateTime),
+        Some("DateTime") => quote!(Timestamp),
+        _ => {
+            return Err(Error::new_spanned(
+                ty,
+                "unsupported field type, set the column type by `#[sqlz(col_type = \"...\")]`",
+            ))
+        }
+    };
+    Ok(quote!(::sqlz::ColumnType::#t))
+}
+
+fn last_ident(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+/// `T` of `name<T>`
+fn generic_of<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(a) if a.args.len() == 1 => match &a.args[0] {
+            GenericArgument::Type(t) => Some(t),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,248 @@
+//! # Sqlz Derive
+//!
+//! `#[derive(SqlzTable)]` implements `sqlz::SqlzTable` for a struct with named
+//! fields, which gives the `TableCreate` of the struct.
+//!
+//! ## Struct attributes
+//! - `#[sqlz(table = "name")]`: table name, snake case struct name by default
+//! - `#[sqlz(primary_key("a", "b"))]`: composite primary key
+//! - `#[sqlz(unique(name = "...", columns("a", "b")))]`: unique key
+//! - `#[sqlz(index(name = "...", columns("a", "b")))]`: index, given by
+//!   `SqlzTable::indexes`
+//! - `#[sqlz(comment = "...")]`: table description
+//!
+//! ## Field attributes
+//! - `#[sqlz(primary_key)]`, `#[sqlz(unique)]`, `#[sqlz(index)]`: column key
+//! - `#[sqlz(rename = "name")]`: column name, field name by default
+//! - `#[sqlz(col_type = "VarChar(Some(50))")]`: column type instead of the
+//!   one mapped from the field type
+//! - `#[sqlz(auto_increment)]`
+//! - `#[sqlz(comment = "...")]`: column description
+//! - `#[sqlz(foreign_key(table = "...", column = "id", on_delete = "Cascade"))]`
+//! - `#[sqlz(skip)]`: not a column
+//!
+//! `Option<T>` fields are nullable, others are `NOT NULL`. Unnamed constraints
+//! are named as `<table>_<columns>_key`, `<table>_<columns>_index` and
+//! `<table>_<column>_fkey`.
+
+mod attr;
+mod column_type;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Result};
+
+use attr::{FieldAttrs, NamedColumns, TableAttrs};
+
+#[proc_macro_derive(SqlzTable, attributes(sqlz))]
+pub fn derive_sqlz_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    input,
+                    "expected a struct with named fields",
+                ))
+            }
+        },
+        _ => return Err(Error::new_spanned(input, "expected a struct")),
+    };
+
+    let table_attrs = TableAttrs::parse(&input.attrs)?;
+    let table = table_attrs
+        .name
+        .clone()
+        .unwrap_or_else(|| snake_case(&input.ident.to_string()));
+
+    let mut names = vec![];
+    let mut columns = vec![];
+    let mut foreign_keys = vec![];
+    for field in fields.iter() {
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        if attrs.skip {
+            continue;
+        }
+        let name = attrs
+            .rename
+            .clone()
+            .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string());
+        let (col_type, nullable) = column_type::map(&field.ty, attrs.col_type.as_ref())?;
+        let key = match &attrs.key {
+            Some(k) => quote!(Some(::sqlz::ColumnKey::#k)),
+            None => quote!(None),
+        };
+        let auto_increment = if attrs.auto_increment {
+            quote!(Some(true))
+        } else {
+            quote!(None)
+        };
+        let comment = option_string(&attrs.comment);
+        columns.push(quote! {
+            ::sqlz::Column {
+                name: #name.to_owned(),
+                col_type: #col_type,
+                null: Some(#nullable),
+                key: #key,
+                auto_increment: #auto_increment,
+                comment: #comment,
+                ..::std::default::Default::default()
+            }
+        });
+
+        if let Some(fk) = &attrs.foreign_key {
+            let fk_name = fk
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("{}_{}_fkey", table, name));
+            let to_table = &fk.table;
+            let to_column = &fk.column;
+            let on_delete = action(&fk.on_delete);
+            let on_update = action(&fk.on_update);
+            foreign_keys.push(quote! {
+                ::sqlz::ForeignKey {
+                    name: #fk_name.to_owned(),
+                    from: ::sqlz::ForeignKeyDir {
+                        table: #table.to_owned(),
+                        columns: vec![#name.to_owned()],
+                    },
+                    to: ::sqlz::ForeignKeyDir {
+                        table: #to_table.to_owned(),
+                        columns: vec![#to_column.to_owned()],
+                    },
+                    on_delete: #on_delete,
+                    on_update: #on_update,
+                }
+            });
+        }
+        names.push(name);
+    }
+
+    let primary_key = match &table_attrs.primary_key {
+        Some(pk) => {
+            check_columns(&names, pk, input)?;
+            quote!(Some(vec![#(#pk.to_owned()),*]))
+        }
+        None => quote!(None),
+    };
+
+    let unique_keys = if table_attrs.unique_keys.is_empty() {
+        quote!(None)
+    } else {
+        let keys = table_attrs
+            .unique_keys
+            .iter()
+            .map(|u| {
+                let (name, columns) = named_columns(&names, &table, u, "key")?;
+                Ok(quote! {
+                    ::sqlz::UniqueKey {
+                        name: #name.to_owned(),
+                        columns: vec![#(#columns.to_owned()),*],
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        quote!(Some(vec![#(#keys),*]))
+    };
+
+    let indexes = table_attrs
+        .indexes
+        .iter()
+        .map(|i| {
+            let (name, columns) = named_columns(&names, &table, i, "index")?;
+            Ok(quote! {
+                ::sqlz::IndexCreate {
+                    name: #name.to_owned(),
+                    table: #table.to_owned(),
+                    columns: vec![#(::sqlz::Order {
+                        name: #columns.to_owned(),
+                        order: None,
+                    }),*],
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let comment = option_string(&table_attrs.comment);
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::sqlz::SqlzTable for #ident #ty_generics #where_clause {
+            fn table_create() -> ::sqlz::TableCreate {
+                ::sqlz::TableCreate {
+                    name: #table.to_owned(),
+                    columns: vec![#(#columns),*],
+                    foreign_keys: vec![#(#foreign_keys),*],
+                    primary_key: #primary_key,
+                    unique_keys: #unique_keys,
+                    comment: #comment,
+                    ..::std::default::Default::default()
+                }
+            }
+
+            fn indexes() -> Vec<::sqlz::IndexCreate> {
+                vec![#(#indexes),*]
+            }
+        }
+    })
+}
+
+fn option_string(s: &Option<String>) -> TokenStream2 {
+    match s {
+        Some(s) => quote!(Some(#s.to_owned())),
+        None => quote!(None),
+    }
+}
+
+fn action(action: &Option<syn::Ident>) -> TokenStream2 {
+    match action {
+        Some(a) => quote!(::sqlz::ForeignKeyAction::#a),
+        None => quote!(::sqlz::ForeignKeyAction::NoAction),
+    }
+}
+
+/// name of a unique key or index, and its columns
+fn named_columns(
+    names: &[String],
+    table: &str,
+    named: &NamedColumns,
+    suffix: &str,
+) -> Result<(String, Vec<String>)> {
+    check_columns(names, &named.columns, &named.meta)?;
+    let name = named
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}_{}_{}", table, named.columns.join("_"), suffix));
+    Ok((name, named.columns.clone()))
+}
+
+fn check_columns<T: quote::ToTokens>(names: &[String], columns: &[String], span: T) -> Result<()> {
+    match columns.iter().find(|c| !names.contains(c)) {
+        Some(c) => Err(Error::new_spanned(span, format!("unknown column `{}`", c))),
+        None => Ok(()),
+    }
+}
+
+fn snake_case(s: &str) -> String {
+    let mut res = String::new();
+    for (i, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                res.push('_');
+            }
+            res.extend(c.to_lowercase());
+        } else {
+            res.push(c);
+        }
+    }
+    res
+}
@@ -0,0 +1,107 @@
+use sqlz::{Dialect, SqlBuilder, SqlzTable, TableCreate};
+
+#[allow(dead_code)]
+#[derive(SqlzTable)]
+#[sqlz(unique(columns("org_id", "email")))]
+#[sqlz(index(name = "idx_users_name", columns("last_name", "first_name")))]
+#[sqlz(comment = "accounts of users")]
+struct UserAccount {
+    #[sqlz(primary_key, auto_increment)]
+    id: i64,
+    org_id: i32,
+    #[sqlz(rename = "email", comment = "login e-mail")]
+    mail: String,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    #[sqlz(col_type = "VarChar(Some(2))", index)]
+    country: String,
+    #[sqlz(col_type = "Decimal(10, 2)")]
+    balance: Option<f64>,
+    active: bool,
+    tags: Vec<String>,
+    avatar: Option<Vec<u8>>,
+    #[sqlz(foreign_key(table = "users", on_delete = "SetNull"))]
+    referrer_id: Option<i64>,
+    #[sqlz(skip)]
+    cache: Vec<u8>,
+}
+
+#[allow(dead_code)]
+#[derive(SqlzTable)]
+#[sqlz(table = "memberships", primary_key("user_id", "group_id"))]
+struct Membership {
+    #[sqlz(foreign_key(
+        name = "fk_user",
+        table = "users",
+        column = "id",
+        on_delete = "Cascade"
+    ))]
+    user_id: i64,
+    group_id: i64,
+}
+
+#[test]
+fn derive_table() {
+    let json = r#"
+    {
+        "name": "user_account",
+        "columns": [
+            {"name": "id", "col_type": "BigInt", "null": false, "key": "Primary", "auto_increment": true},
+            {"name": "org_id", "col_type": "Int", "null": false, "key": null},
+            {"name": "email", "col_type": "VarChar", "null": false, "key": null, "comment": "login e-mail"},
+            {"name": "first_name", "col_type": "VarChar", "null": true, "key": null},
+            {"name": "last_name", "col_type": "VarChar", "null": true, "key": null},
+            {"name": "country", "col_type": {"VarChar": 2}, "null": false, "key": "Multiple"},
+            {"name": "balance", "col_type": {"Decimal": [10, 2]}, "null": true, "key": null},
+            {"name": "active", "col_type": "Bool", "null": false, "key": null},
+            {"name": "tags", "col_type": {"Array": "VarChar"}, "null": false, "key": null},
+            {"name": "avatar", "col_type": "Binary", "null": true, "key": null},
+            {"name": "referrer_id", "col_type": "BigInt", "null": true, "key": null}
+        ],
+        "foreign_keys": [
+            {
+                "name": "user_account_referrer_id_fkey",
+                "from": {"table": "user_account", "columns": ["referrer_id"]},
+                "to": {"table": "users", "columns": ["id"]},
+                "on_delete": "SetNull",
+                "on_update": "NoAction"
+            }
+        ],
+        "unique_keys": [
+            {"name": "user_account_org_id_email_key", "columns": ["org_id", "email"]}
+        ],
+        "comment": "accounts of users"
+    }"#;
+    let hand_written: TableCreate = serde_json::from_str(json).unwrap();
+
+    let derived = UserAccount::table_create();
+
+    assert_eq!(derived, hand_written);
+    assert_eq!(
+        serde_json::to_string(&derived).unwrap(),
+        serde_json::to_string(&hand_written).unwrap()
+    );
+
+    let indexes = serde_json::to_string(&UserAccount::indexes()).unwrap();
+    assert_eq!(
+        indexes,
+        r#"[{"name":"idx_users_name","table":"user_account","columns":[{"name":"last_name","order":null},{"name":"first_name","order":null}]}]"#
+    );
+}
+
+#[test]
+fn derive_composite_key() {
+    let table = Membership::table_create();
+
+    assert_eq!(
+        SqlBuilder::new(Dialect::Postgres)
+            .table_create(&table)
+            .unwrap(),
+        vec![concat!(
+            r#"CREATE TABLE "memberships" ("user_id" BIGINT NOT NULL, "group_id" BIGINT NOT NULL, "#,
+            r#"PRIMARY KEY ("user_id", "group_id"), CONSTRAINT "fk_user" FOREIGN KEY ("user_id") "#,
+            r#"REFERENCES "users" ("id") ON DELETE CASCADE ON UPDATE NO ACTION)"#
+        )]
+    );
+    assert!(Membership::indexes().is_empty());
+}
@@ -0,0 +1,323 @@
+use super::writer::SqlWriter;
+use super::Dialect;
+use crate::filter::validate_filter;
+use crate::{
+    Aggregate, ArithmeticOperator, Case, ColumnExpr, DatePart, Function, Shift, SqlzError,
+    SqlzResult, Window, WindowFunction,
+};
+
+impl SqlWriter {
+    pub(crate) fn write_column_expr(&mut self, expr: &ColumnExpr) -> SqlzResult<()> {
+        match expr {
+            ColumnExpr::Column(c) => self.push_ident(c),
+            ColumnExpr::Value(v) => self.push_value(v)?,
+            ColumnExpr::Arithmetic(a) => {
+                let (left, op, right) = a.as_ref();
+                self.push("(");
+                self.write_column_expr(left)?;
+                self.push(match op {
+                    ArithmeticOperator::Add => " + ",
+                    ArithmeticOperator::Sub => " - ",
+                    ArithmeticOperator::Mul => " * ",
+                    ArithmeticOperator::Div => " / ",
+                    ArithmeticOperator::Mod => " % ",
+                });
+                self.write_column_expr(right)?;
+                self.push(")");
+            }
+            ColumnExpr::Case(c) => self.write_case(c)?,
+            ColumnExpr::Coalesce(v) if v.is_empty() => {
+                return Err(SqlzError::InvalidValue(
+                    "`COALESCE` without arguments".to_owned(),
+                ))
+            }
+            ColumnExpr::Coalesce(v) => {
+                self.push("COALESCE(");
+                self.push_list(v, ", ", |w, e| w.write_column_expr(e))?;
+                self.push(")");
+            }
+            ColumnExpr::Cast(c) => {
+                let (e, t) = c.as_ref();
+                let t = self.dialect.cast_type(t)?;
+                self.push("CAST(");
+                self.write_column_expr(e)?;
+                self.push(" AS ");
+                self.push(&t);
+                self.push(")");
+            }
+            ColumnExpr::Function(f) => self.write_function(f)?,
+        }
+        Ok(())
+    }
+
+    fn write_case(&mut self, case: &Case) -> SqlzResult<()> {
+        if case.when.is_empty() {
+            return Err(SqlzError::InvalidValue("`CASE` without `WHEN`".to_owned()));
+        }
+        self.push("CASE");
+        for (i, w) in case.when.iter().enumerate() {
+            validate_filter(&w.condition, &format!("/when/{}/condition", i))?;
+            if w.condition.is_empty() {
+                return Err(SqlzError::InvalidValue(
+                    "`WHEN` without condition".to_owned(),
+                ));
+            }
+            self.push(" WHEN ");
+            self.write_expressions(&w.condition)?;
+            self.push(" THEN ");
+            self.write_column_expr(&w.then)?;
+        }
+        if let Some(e) = &case.otherwise {
+            self.push(" ELSE ");
+            self.write_column_expr(e)?;
+        }
+        self.push(" END");
+        Ok(())
+    }
+
+    fn write_function(&mut self, function: &Function) -> SqlzResult<()> {
+        match function {
+            Function::Upper(e) => self.write_call("UPPER", e),
+            Function::Lower(e) => self.write_call("LOWER", e),
+            Function::Trim(e) => self.write_call("TRIM", e),
+            // MySql `LENGTH` counts bytes
+            Function::Length(e) if self.dialect == Dialect::MySql => {
+                self.write_call("CHAR_LENGTH", e)
+            }
+            Function::Length(e) => self.write_call("LENGTH", e),
+            Function::Concat(v) if v.is_empty() => Err(SqlzError::InvalidValue(
+                "`CONCAT` without arguments".to_owned(),
+            )),
+            // MySql `||` is a logical or, and Postgres `CONCAT` ignores nulls
+            Function::Concat(v) if self.dialect == Dialect::MySql => {
+                self.push("CONCAT(");
+                self.push_list(v, ", ", |w, e| w.write_column_expr(e))?;
+                self.push(")");
+                Ok(())
+            }
+            Function::Concat(v) => {
+                self.push("(");
+                self.push_list(v, " || ", |w, e| w.write_column_expr(e))?;
+                self.push(")");
+                Ok(())
+            }
+            Function::Substring(s) => {
+                let (e, start, length) = s.as_ref();
+                self.push("SUBSTR(");
+                self.write_column_expr(e)?;
+                self.push(&format!(", {}", start));
+                if let Some(l) = length {
+                    self.push(&format!(", {}", l));
+                }
+                self.push(")");
+                Ok(())
+            }
+            Function::CurrentTimestamp => {
+                self.push("CURRENT_TIMESTAMP");
+                Ok(())
+            }
+            Function::Extract(x) => {
+                let (part, e) = x.as_ref();
+                self.push("CAST(");
+                if self.dialect == Dialect::Sqlite {
+                    let format = match part {
+                        DatePart::Year => "%Y",
+                        DatePart::Month => "%m",
+                        DatePart::Day => "%d",
+                        DatePart::Hour => "%H",
+                        DatePart::Minute => "%M",
+                        DatePart::Second => "%S",
+                    };
+                    self.push(&format!("strftime({}, ", self.dialect.quote_string(format)));
+                    self.write_column_expr(e)?;
+                    self.push(")");
+                } else {
+                    // Postgres seconds have fractions, which a cast would round
+                    let floor = self.dialect == Dialect::Postgres && *part == DatePart::Second;
+                    if floor {
+                        self.push("FLOOR(");
+                    }
+                    self.push(&format!("EXTRACT({} FROM ", date_part(part)));
+                    self.write_column_expr(e)?;
+                    self.push(")");
+                    if floor {
+                        self.push(")");
+                    }
+                }
+                self.push(match self.dialect {
+                    Dialect::MySql => " AS SIGNED)",
+                    _ => " AS INTEGER)",
+                });
+                Ok(())
+            }
+            Function::DateTrunc(x) => {
+                let (part, e) = x.as_ref();
+                match self.dialect {
+                    Dialect::Postgres => {
+                        let part = date_part(part).to_lowercase();
+                        self.push(&format!(
+                            "DATE_TRUNC({}, ",
+                            self.dialect.quote_string(&part)
+                        ));
+                        self.write_column_expr(e)?;
+                        self.push(")");
+                    }
+                    Dialect::MySql => {
+                        let format = match part {
+                            DatePart::Year => "%Y-01-01 00:00:00",
+                            DatePart::Month => "%Y-%m-01 00:00:00",
+                            DatePart::Day => "%Y-%m-%d 00:00:00",
+                            DatePart::Hour => "%Y-%m-%d %H:00:00",
+                            DatePart::Minute => "%Y-%m-%d %H:%i:00",
+                            DatePart::Second => "%Y-%m-%d %H:%i:%s",
+                        };
+                        self.push("CAST(DATE_FORMAT(");
+                        self.write_column_expr(e)?;
+                        self.push(&format!(
+                            ", {}) AS DATETIME)",
+                            self.dialect.quote_string(format)
+                        ));
+                    }
+                    Dialect::Sqlite => {
+                        let format = match part {
+                            DatePart::Year => "%Y-01-01 00:00:00",
+                            DatePart::Month => "%Y-%m-01 00:00:00",
+                            DatePart::Day => "%Y-%m-%d 00:00:00",
+                            DatePart::Hour => "%Y-%m-%d %H:00:00",
+                            DatePart::Minute => "%Y-%m-%d %H:%M:00",
+                            DatePart::Second => "%Y-%m-%d %H:%M:%S",
+                        };
+                        self.push(&format!("strftime({}, ", self.dialect.quote_string(format)));
+                        self.write_column_expr(e)?;
+                        self.push(")");
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn write_call(&mut self, name: &str, arg: &ColumnExpr) -> SqlzResult<()> {
+        self.push(name);
+        self.push("(");
+        self.write_column_expr(arg)?;
+        self.push(")");
+        Ok(())
+    }
+
+    /// `f(...) OVER (PARTITION BY ... ORDER BY ...)`
+    pub(crate) fn write_window(&mut self, window: &Window) -> SqlzResult<()> {
+        match &window.window {
+            WindowFunction::RowNumber => self.push("ROW_NUMBER()"),
+            WindowFunction::Rank => self.push("RANK()"),
+            WindowFunction::DenseRank => self.push("DENSE_RANK()"),
+            WindowFunction::Lag(s) => self.write_shift("LAG", s)?,
+            WindowFunction::Lead(s) => self.write_shift("LEAD", s)?,
+            WindowFunction::Aggregate((function, column)) => {
+                self.write_aggregate(&Aggregate {
+                    function: function.clone(),
+                    column: column.clone(),
+                    alias: None,
+                })?;
+            }
+        }
+        self.push(" OVER (");
+        if !window.partition_by.is_empty() {
+            self.push("PARTITION BY ");
+            self.push_list(&window.partition_by, ", ", |w, c| {
+                w.push_ident(c);
+                Ok(())
+            })?;
+            if !window.order.is_empty() {
+                self.push(" ");
+            }
+        }
+        if !window.order.is_empty() {
+            self.push("ORDER BY ");
+            self.write_order_list(&window.order);
+        }
+        self.push(")");
+        Ok(())
+    }
+
+    fn write_shift(&mut self, name: &str, shift: &Shift) -> SqlzResult<()> {
+        self.push(name);
+        self.push("(");
+        self.push_ident(&shift.column);
+        if shift.offset.is_some() || shift.default.is_some() {
+            self.push(&format!(", {}", shift.offset.unwrap_or(1)));
+        }
+        if let Some(d) = &shift.default {
+            self.push(", ");
+            self.push_value(d)?;
+        }
+        self.push(")");
+        Ok(())
+    }
+}
+
+fn date_part(part: &DatePart) -> &'static str {
+    match part {
+        DatePart::Year => "YEAR",
+        DatePart::Month => "MONTH",
+        DatePart::Day => "DAY",
+        DatePart::Hour => "HOUR",
+        DatePart::Minute => "MINUTE",
+        DatePart::Second => "SECOND",
+    }
+}
+
+#[cfg(test)]
+mod tests_computed_builder {
+    use crate::{Dialect, Select, SqlBuilder};
+
+    const SELECT: &str = r#"
+    {
+        "table": "orders",
+        "columns": [
+            "id",
+            {"expr": {"Arithmetic": [{"Column": "price"}, "Mul", {"Coalesce": [{"Column": "qty"}, {"Value": 1}]}]}, "alias": "total"},
+            {"expr": {"Case": {
+                "when": [{"condition": [{"column": "price", "equation": {"Greater": 100}}], "then": {"Value": "high"}}],
+                "else": {"Value": "low"}
+            }}, "alias": "tier"},
+            {"expr": {"Cast": [{"Column": "code"}, "Int"]}, "alias": "code"},
+            {"expr": {"Function": {"Concat": [{"Function": {"Upper": {"Column": "region"}}}, {"Value": "-"}, {"Column": "city"}]}}, "alias": "place"},
+            {"expr": {"Function": {"DateTrunc": ["Month", {"Column": "created"}]}}, "alias": "month"},
+            {"expr": {"Function": {"Extract": ["Year", {"Column": "created"}]}}, "alias": "year"},
+            {"expr": {"Function": {"Extract": ["Second", {"Column": "created"}]}}, "alias": "second"},
+            {"window": "RowNumber", "partition_by": ["region"], "order": [{"name": "created", "order": "Desc"}], "alias": "rn"},
+            {"window": {"Lag": {"column": "price", "default": 0}}, "order": [{"name": "created", "order": null}], "alias": "prev"},
+            {"window": {"Aggregate": ["Sum", "price"]}, "partition_by": ["region"], "order": [{"name": "created", "order": null}], "alias": "running"}
+        ],
+        "filter": null,
+        "order": [{"name": "month", "order": null}],
+        "limit": null,
+        "offset": null
+    }"#;
+
+    fn render(dialect: Dialect) -> String {
+        let select: Select = serde_json::from_str(SELECT).unwrap();
+        SqlBuilder::new(dialect).select(&select).unwrap()
+    }
+
+    #[test]
+    fn computed_postgres() {
+        assert_eq!(
+            render(Dialect::Postgres),
+            r#"SELECT "id", ("price" * COALESCE("qty", 1)) AS "total", CASE WHEN "price" > 100 THEN 'high' ELSE 'low' END AS "tier", CAST("code" AS INTEGER) AS "code", (UPPER("region") || '-' || "city") AS "place", DATE_TRUNC('month', "created") AS "month", CAST(EXTRACT(YEAR FROM "created") AS INTEGER) AS "year", CAST(FLOOR(EXTRACT(SECOND FROM "created")) AS INTEGER) AS "second", ROW_NUMBER() OVER (PARTITION BY "region" ORDER BY "created" DESC) AS "rn", LAG("price", 1, 0) OVER (ORDER BY "created") AS "prev", SUM("price") OVER (PARTITION BY "region" ORDER BY "created") AS "running" FROM "orders" ORDER BY "month""#
+        );
+    }
+
+    #[test]
+    fn computed_mysql_sqlite() {
+        assert_eq!(
+            render(Dialect::MySql),
+            r#"SELECT `id`, (`price` * COALESCE(`qty`, 1)) AS `total`, CASE WHEN `price` > 100 THEN 'high' ELSE 'low' END AS `tier`, CAST(`code` AS SIGNED) AS `code`, CONCAT(UPPER(`region`), '-', `city`) AS `place`, CAST(DATE_FORMAT(`created`, '%Y-%m-01 00:00:00') AS DATETIME) AS `month`, CAST(EXTRACT(YEAR FROM `created`) AS SIGNED) AS `year`, CAST(EXTRACT(SECOND FROM `created`) AS SIGNED) AS `second`, ROW_NUMBER() OVER (PARTITION BY `region` ORDER BY `created` DESC) AS `rn`, LAG(`price`, 1, 0) OVER (ORDER BY `created`) AS `prev`, SUM(`price`) OVER (PARTITION BY `region` ORDER BY `created`) AS `running` FROM `orders` ORDER BY `month`"#
+        );
+        assert_eq!(
+            render(Dialect::Sqlite),
+            r#"SELECT "id", ("price" * COALESCE("qty", 1)) AS "total", CASE WHEN "price" > 100 THEN 'high' ELSE 'low' END AS "tier", CAST("code" AS INTEGER) AS "code", (UPPER("region") || '-' || "city") AS "place", strftime('%Y-%m-01 00:00:00', "created") AS "month", CAST(strftime('%Y', "created") AS INTEGER) AS "year", CAST(strftime('%S', "created") AS INTEGER) AS "second", ROW_NUMBER() OVER (PARTITION BY "region" ORDER BY "created" DESC) AS "rn", LAG("price", 1, 0) OVER (ORDER BY "created") AS "prev", SUM("price") OVER (PARTITION BY "region" ORDER BY "created") AS "running" FROM "orders" ORDER BY "month""#
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use super::writer::SqlWriter;
+use super::Dialect;
+use crate::{Delete, SqlzError, SqlzResult};
+
+impl SqlWriter {
+    pub(crate) fn write_delete(&mut self, delete: &Delete) -> SqlzResult<()> {
+        delete.check()?;
+
+        let returning = delete.returning.as_deref().unwrap_or_default();
+        if !returning.is_empty() && self.dialect == Dialect::MySql {
+            return Err(SqlzError::Unsupported(
+                "`RETURNING` in MySql delete".to_owned(),
+            ));
+        }
+
+        self.push("DELETE FROM ");
+        self.push_ident(&delete.table);
+
+        match (delete.limit, self.dialect) {
+            (Some(l), Dialect::MySql) => {
+                self.write_filter(&delete.filter)?;
+                self.push(&format!(" LIMIT {}", l));
+            }
+            // Postgres and Sqlite (by default) do not accept `LIMIT` in delete,
+            // rows are selected by their physical row id instead
+            (Some(l), _) => {
+                let row_id = if self.dialect == Dialect::Postgres {
+                    "ctid"
+                } else {
+                    "rowid"
+                };
+                self.push(&format!(" WHERE {} IN (SELECT {} FROM ", row_id, row_id));
+                self.push_ident(&delete.table);
+                self.write_filter(&delete.filter)?;
+                self.push(&format!(" LIMIT {})", l));
+            }
+            (None, _) => self.write_filter(&delete.filter)?,
+        }
+
+        if !returning.is_empty() {
+            self.push(" RETURNING ");
+            self.push_list(returning, ", ", |w, c| {
+                w.push_ident(c);
+                Ok(())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_delete_builder {
+    use crate::{DataEnum, Delete, Dialect, SqlBuilder, SqlzError};
+
+    #[test]
+    fn delete() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "filter": [{"column":"c1","equation":{"Less":23}}],
+            "returning": ["c1", "c2"]
+        }"#;
+        let delete: Delete = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).delete(&delete).unwrap(),
+            r#"DELETE FROM "sqlz" WHERE "c1" < 23 RETURNING "c1", "c2""#
+        );
+
+        let (sql, params) = SqlBuilder::new(Dialect::Sqlite)
+            .delete_with_params(&delete)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"DELETE FROM "sqlz" WHERE "c1" < ? RETURNING "c1", "c2""#
+        );
+        assert_eq!(params, vec![DataEnum::Integer(23)]);
+
+        assert!(matches!(
+            SqlBuilder::new(Dialect::MySql).delete(&delete),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn delete_limit() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "filter": [{"column":"c1","equation":{"Less":23}}],
+            "limit": 10
+        }"#;
+        let delete: Delete = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).delete(&delete).unwrap(),
+            "DELETE FROM `sqlz` WHERE `c1` < 23 LIMIT 10"
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).delete(&delete).unwrap(),
+            r#"DELETE FROM "sqlz" WHERE ctid IN (SELECT ctid FROM "sqlz" WHERE "c1" < 23 LIMIT 10)"#
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite).delete(&delete).unwrap(),
+            r#"DELETE FROM "sqlz" WHERE rowid IN (SELECT rowid FROM "sqlz" WHERE "c1" < 23 LIMIT 10)"#
+        );
+    }
+
+    #[test]
+    fn delete_unfiltered() {
+        let json = r#"{"table": "sqlz"}"#;
+        let mut delete: Delete = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).delete(&delete),
+            Err(SqlzError::Unfiltered("sqlz".to_owned()))
+        );
+
+        delete.allow_unfiltered = Some(true);
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).delete(&delete).unwrap(),
+            r#"DELETE FROM "sqlz""#
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use serde::{Deserialize, Serialize};
+
+use crate::parser::table::parse_column_type;
+use crate::{ColumnType, ForeignKeyAction, SqlzError, SqlzResult};
+
+/// database dialect, decides how identifiers and literals are written
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    Postgres,
+    MySql,
+    Sqlite,
+}
+
+impl Dialect {
+    /// quote an identifier. A dotted name such as `t.c` is treated as a
+    /// qualified name and each part is quoted separately, `*` is kept as is.
+    pub fn quote_ident(&self, ident: &str) -> String {
+        ident
+            .split('.')
+            .map(|part| match part {
+                "*" => part.to_owned(),
+                _ => self.quote_ident_part(part),
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    fn quote_ident_part(&self, part: &str) -> String {
+        match self {
+            Dialect::MySql => format!("`{}`", part.replace('`', "``")),
+            Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", part.replace('"', "\"\"")),
+        }
+    }
+
+    /// quote a string literal. MySql treats backslash as an escape character
+    /// by default, so it needs to be escaped as well.
+    pub fn quote_string(&self, s: &str) -> String {
+        match self {
+            Dialect::MySql => {
+                let mut res = String::with_capacity(s.len() + 2);
+                res.push('\'');
+                for c in s.chars() {
+                    match c {
+                        '\'' => res.push_str("''"),
+                        '\\' => res.push_str("\\\\"),
+                        '\0' => res.push_str("\\0"),
+                        _ => res.push(c),
+                    }
+                }
+                res.push('\'');
+                res
+            }
+            Dialect::Postgres | Dialect::Sqlite => format!("'{}'", s.replace('\'', "''")),
+        }
+    }
+
+    /// bind value placeholder, `n` starts from 1
+    pub fn placeholder(&self, n: usize) -> String {
+        match self {
+            Dialect::Postgres => format!("${}", n),
+            Dialect::MySql | Dialect::Sqlite => "?".to_owned(),
+        }
+    }
+
+    /// native type name of a column type, unsupported types are reported as
+    /// `SqlzError::Unsupported`
+    pub fn column_type(&self, col_type: &ColumnType) -> SqlzResult<String> {
+        let t = match (self, col_type) {
+            (_, ColumnType::Bool) => "BOOLEAN",
+            (_, ColumnType::SmallInt) => "SMALLINT",
+            (Dialect::MySql, ColumnType::Int) => "INT",
+            (_, ColumnType::Int) => "INTEGER",
+            (_, ColumnType::BigInt) => "BIGINT",
+            (Dialect::MySql, ColumnType::Float) => "FLOAT",
+            (_, ColumnType::Float) => "REAL",
+            (Dialect::Postgres, ColumnType::Double) => "DOUBLE PRECISION",
+            (_, ColumnType::Double) => "DOUBLE",
+            (Dialect::MySql, ColumnType::Decimal(p, s)) => {
+                return Ok(format!("DECIMAL({}, {})", p, s))
+            }
+            (_, ColumnType::Decimal(p, s)) => return Ok(format!("NUMERIC({}, {})", p, s)),
+            (_, ColumnType::Date) => "DATE",
+            (_, ColumnType::Time) => "TIME",
+            (Dialect::Postgres, ColumnType::DateTime) => "TIMESTAMP",
+            (_, ColumnType::DateTime) => "DATETIME",
+            (Dialect::Postgres, ColumnType::Timestamp) => "TIMESTAMPTZ",
+            (_, ColumnType::Timestamp) => "TIMESTAMP",
+            (_, ColumnType::Char(Some(n))) => return Ok(format!("CHAR({})", n)),
+            (_, ColumnType::Char(None)) => "CHAR",
+            (_, ColumnType::VarChar(Some(n))) => return Ok(format!("VARCHAR({})", n)),
+            // MySql requires a length for `VARCHAR`
+            (Dialect::MySql, ColumnType::VarChar(None)) => "VARCHAR(255)",
+            (_, ColumnType::VarChar(None)) => "VARCHAR",
+            (_, ColumnType::Text) => "TEXT",
+            (Dialect::Sqlite, ColumnType::Json) => "TEXT",
+            (_, ColumnType::Json) => "JSON",
+            (Dialect::Postgres, ColumnType::Binary) => "BYTEA",
+            (_, ColumnType::Binary) => "BLOB",
+            (Dialect::Postgres, ColumnType::Uuid) => "UUID",
+            (Dialect::MySql, ColumnType::Uuid) => "CHAR(36)",
+            (Dialect::Sqlite, ColumnType::Uuid) => "TEXT",
+            (Dialect::MySql, ColumnType::Enum(v)) => {
+                let v = v.iter().map(|e| self.quote_string(e)).collect::<Vec<_>>();
+                return Ok(format!("ENUM({})", v.join(", ")));
+            }
+            (Dialect::Postgres, ColumnType::Array(t)) => {
+                return Ok(format!("{}[]", self.column_type(t)?))
+            }
+            // Postgres enum is a named type created by `CREATE TYPE`
+            (_, ColumnType::Enum(_)) | (_, ColumnType::Array(_)) => {
+                return Err(SqlzError::Unsupported(format!(
+                    "column type {} in {:?}",
+                    col_type.name(),
+                    self
+                )))
+            }
+        };
+        Ok(t.to_owned())
+    }
+
+    /// target type name of `CAST`, which is limited to a few types in MySql
+    pub fn cast_type(&self, col_type: &ColumnType) -> SqlzResult<String> {
+        if *self != Dialect::MySql {
+            return self.column_type(col_type);
+        }
+        let t = match col_type {
+            ColumnType::Bool | ColumnType::SmallInt | ColumnType::Int | ColumnType::BigInt => {
+                "SIGNED"
+            }
+            ColumnType::Float => "FLOAT",
+            ColumnType::Double => "DOUBLE",
+            ColumnType::Decimal(p, s) => return Ok(format!("DECIMAL({}, {})", p, s)),
+            ColumnType::Date => "DATE",
+            ColumnType::Time => "TIME",
+            ColumnType::DateTime | ColumnType::Timestamp => "DATETIME",
+            ColumnType::Char(Some(n)) | ColumnType::VarChar(Some(n)) => {
+                return Ok(format!("CHAR({})", n))
+            }
+            ColumnType::Char(None)
+            | ColumnType::VarChar(None)
+            | ColumnType::Text
+            | ColumnType::Uuid
+            | ColumnType::Enum(_) => "CHAR",
+            ColumnType::Json => "JSON",
+            ColumnType::Binary => "BINARY",
+            ColumnType::Array(_) => {
+                return Err(SqlzError::Unsupported(format!(
+                    "cast to {} in {:?}",
+                    col_type.name(),
+                    self
+                )))
+            }
+        };
+        Ok(t.to_owned())
+    }
+
+    pub fn supports_column_type(&self, col_type: &ColumnType) -> bool {
+        self.column_type(col_type).is_ok()
+    }
+
+    /// column type of a native type name, as reported by the database, e.g.
+    /// `character varying(20)`. MySql `tinyint(1)` is taken as boolean, and a
+    /// `timestamp` is without time zone only in Postgres.
+    pub fn native_column_type(&self, native: &str) -> SqlzResult<ColumnType> {
+        if *self == Dialect::MySql && native.eq_ignore_ascii_case("tinyint(1)") {
+            return Ok(ColumnType::Bool);
+        }
+        let col_type = parse_column_type(native).map_err(|_| {
+            SqlzError::Unsupported(format!("native column type {} in {:?}", native, self))
+        })?;
+        let timestamp = native.to_ascii_lowercase().starts_with("timestamp");
+        match col_type {
+            ColumnType::DateTime if timestamp && *self != Dialect::Postgres => {
+                Ok(ColumnType::Timestamp)
+            }
+            col_type => Ok(col_type),
+        }
+    }
+
+    /// foreign key referential action
+    pub fn foreign_key_action(&self, action: &ForeignKeyAction) -> SqlzResult<&'static str> {
+        match (self, action) {
+            (_, ForeignKeyAction::Restrict) => Ok("RESTRICT"),
+            (_, ForeignKeyAction::Cascade) => Ok("CASCADE"),
+            (_, ForeignKeyAction::SetNull) => Ok("SET NULL"),
+            (_, ForeignKeyAction::NoAction) => Ok("NO ACTION"),
+            // InnoDB rejects `SET DEFAULT`
+            (Dialect::MySql, ForeignKeyAction::SetDefault) => Err(SqlzError::Unsupported(
+                "`SET DEFAULT` foreign key action in MySql".to_owned(),
+            )),
+            (_, ForeignKeyAction::SetDefault) => Ok("SET DEFAULT"),
+        }
+    }
+
+    /// boolean literal
+    pub fn bool_literal(&self, b: bool) -> &'static str {
+        match (self, b) {
+            (Dialect::Sqlite, true) => "1",
+            (Dialect::Sqlite, false) => "0",
+            (_, true) => "TRUE",
+            (_, false) => "FALSE",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_dialect {
+    use super::*;
+
+    #[test]
+    fn quoting() {
+        assert_eq!(Dialect::Postgres.quote_ident("t.c"), r#""t"."c""#);
+        assert_eq!(Dialect::Postgres.quote_ident("t.*"), r#""t".*"#);
+        assert_eq!(Dialect::Sqlite.quote_ident(r#"a"b"#), r#""a""b""#);
+        assert_eq!(Dialect::MySql.quote_ident("a`b"), "`a``b`");
+
+        assert_eq!(Dialect::Postgres.quote_string(r"it's \n"), r"'it''s \n'");
+        assert_eq!(Dialect::Sqlite.quote_string("it's"), "'it''s'");
+        assert_eq!(Dialect::MySql.quote_string(r"it's \n"), r"'it''s \\n'");
+    }
+
+    #[test]
+    fn column_types() {
+        let enum_type = ColumnType::Enum(vec!["a".to_owned(), "b".to_owned()]);
+        let array_type = ColumnType::Array(Box::new(ColumnType::VarChar(Some(8))));
+
+        assert_eq!(
+            Dialect::Postgres.column_type(&array_type).unwrap(),
+            "VARCHAR(8)[]"
+        );
+        assert_eq!(
+            Dialect::MySql.column_type(&enum_type).unwrap(),
+            "ENUM('a', 'b')"
+        );
+        assert_eq!(
+            Dialect::Sqlite
+                .column_type(&ColumnType::Decimal(10, 2))
+                .unwrap(),
+            "NUMERIC(10, 2)"
+        );
+        assert!(!Dialect::Postgres.supports_column_type(&enum_type));
+        assert!(!Dialect::MySql.supports_column_type(&array_type));
+        assert!(!Dialect::Sqlite.supports_column_type(&array_type));
+    }
+}
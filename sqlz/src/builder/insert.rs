@@ -0,0 +1,127 @@
+use super::writer::SqlWriter;
+use crate::{Insert, InsertValues, SqlzError, SqlzResult};
+
+impl SqlWriter {
+    pub(crate) fn write_insert(&mut self, insert: &Insert) -> SqlzResult<()> {
+        self.push("INSERT INTO ");
+        self.push_ident(&insert.table);
+        if !insert.columns.is_empty() {
+            self.push(" (");
+            self.push_list(&insert.columns, ", ", |w, c| {
+                w.push_ident(c);
+                Ok(())
+            })?;
+            self.push(")");
+        }
+
+        match &insert.values {
+            InsertValues::Rows(rows) => {
+                let width = match rows.first() {
+                    Some(_) if !insert.columns.is_empty() => insert.columns.len(),
+                    Some(r) => r.len(),
+                    None => return Err(SqlzError::InvalidValue("insert without rows".to_owned())),
+                };
+                if let Some(r) = rows.iter().find(|r| r.len() != width) {
+                    return Err(SqlzError::InvalidValue(format!(
+                        "insert row of {} values, expected {}",
+                        r.len(),
+                        width
+                    )));
+                }
+
+                self.push(" VALUES ");
+                self.push_list(rows, ", ", |w, r| {
+                    w.push("(");
+                    w.push_list(r, ", ", |w, v| w.push_value(v))?;
+                    w.push(")");
+                    Ok(())
+                })
+            }
+            InsertValues::Select(select) => {
+                self.push(" ");
+                self.write_select(select)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_insert_builder {
+    use crate::{DataEnum, Dialect, Insert, SqlBuilder, SqlzError};
+
+    #[test]
+    fn insert_rows() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": ["c1", "c2"],
+            "values": {"Rows": [[1, "T1"], [2, null]]}
+        }"#;
+        let insert: Insert = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).insert(&insert).unwrap(),
+            r#"INSERT INTO "sqlz" ("c1", "c2") VALUES (1, 'T1'), (2, NULL)"#
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).insert(&insert).unwrap(),
+            "INSERT INTO `sqlz` (`c1`, `c2`) VALUES (1, 'T1'), (2, NULL)"
+        );
+
+        let (sql, params) = SqlBuilder::new(Dialect::Postgres)
+            .insert_with_params(&insert)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"INSERT INTO "sqlz" ("c1", "c2") VALUES ($1, $2), ($3, $4)"#
+        );
+        assert_eq!(
+            params,
+            vec![
+                DataEnum::Integer(1),
+                DataEnum::from("T1"),
+                DataEnum::Integer(2),
+                DataEnum::Null
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_select() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": ["c1"],
+            "values": {"Select": {
+                "table": "sqlz_src",
+                "columns": ["c1"],
+                "filter": [{"column":"c1","equation":{"Greater":23}}],
+                "order": null,
+                "limit": null,
+                "offset": null
+            }}
+        }"#;
+        let insert: Insert = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite).insert(&insert).unwrap(),
+            r#"INSERT INTO "sqlz" ("c1") SELECT "c1" FROM "sqlz_src" WHERE "c1" > 23"#
+        );
+    }
+
+    #[test]
+    fn insert_invalid_rows() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": ["c1", "c2"],
+            "values": {"Rows": [[1, "T1"], [2]]}
+        }"#;
+        let insert: Insert = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Postgres).insert(&insert),
+            Err(SqlzError::InvalidValue(_))
+        ));
+    }
+}
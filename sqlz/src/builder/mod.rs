@@ -0,0 +1,180 @@
+//! Renders sqlz models into Sql strings.
+
+pub mod computed;
+pub mod delete;
+pub mod dialect;
+pub mod insert;
+pub mod schema;
+pub mod select;
+pub mod update;
+pub(crate) mod writer;
+
+pub use dialect::Dialect;
+
+use crate::{
+    DataEnum, Delete, ForeignKeyCreate, ForeignKeyDrop, IndexCreate, IndexDrop, Insert, Migration,
+    Select, SqlzResult, TableAlter, TableCreate, TableDrop, TableRename, TableTruncate, Update,
+    ViewCreate, ViewDrop, ViewRefresh,
+};
+use writer::SqlWriter;
+
+/// Sql string builder of a specific dialect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqlBuilder {
+    dialect: Dialect,
+}
+
+impl SqlBuilder {
+    pub fn new(dialect: Dialect) -> Self {
+        SqlBuilder { dialect }
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    fn render<F>(&self, f: F) -> SqlzResult<String>
+    where
+        F: FnOnce(&mut SqlWriter) -> SqlzResult<()>,
+    {
+        let mut w = SqlWriter::new(self.dialect);
+        f(&mut w)?;
+        Ok(w.finish())
+    }
+
+    fn render_with_params<F>(&self, f: F) -> SqlzResult<(String, Vec<DataEnum>)>
+    where
+        F: FnOnce(&mut SqlWriter) -> SqlzResult<()>,
+    {
+        let mut w = SqlWriter::with_params(self.dialect);
+        f(&mut w)?;
+        Ok(w.finish_with_params())
+    }
+
+    fn render_statements<F>(&self, f: F) -> SqlzResult<Vec<String>>
+    where
+        F: FnOnce(&mut SqlWriter) -> SqlzResult<()>,
+    {
+        let mut w = SqlWriter::new(self.dialect);
+        f(&mut w)?;
+        Ok(w.finish_statements())
+    }
+
+    /// render a `Select` into a Sql string, values are inlined as literals
+    pub fn select(&self, select: &Select) -> SqlzResult<String> {
+        self.render(|w| w.write_select(select))
+    }
+
+    /// render a `Select` into a Sql string with placeholders, and the bind
+    /// values in placeholder order
+    pub fn select_with_params(&self, select: &Select) -> SqlzResult<(String, Vec<DataEnum>)> {
+        self.render_with_params(|w| w.write_select(select))
+    }
+
+    /// render an `Insert` into a Sql string, values are inlined as literals
+    pub fn insert(&self, insert: &Insert) -> SqlzResult<String> {
+        self.render(|w| w.write_insert(insert))
+    }
+
+    /// render an `Insert` into a Sql string with placeholders, and the bind
+    /// values in placeholder order
+    pub fn insert_with_params(&self, insert: &Insert) -> SqlzResult<(String, Vec<DataEnum>)> {
+        self.render_with_params(|w| w.write_insert(insert))
+    }
+
+    /// render an `Update` into a Sql string, values are inlined as literals.
+    /// An unfiltered update is refused unless explicitly allowed.
+    pub fn update(&self, update: &Update) -> SqlzResult<String> {
+        self.render(|w| w.write_update(update))
+    }
+
+    /// render an `Update` into a Sql string with placeholders, and the bind
+    /// values in placeholder order
+    pub fn update_with_params(&self, update: &Update) -> SqlzResult<(String, Vec<DataEnum>)> {
+        self.render_with_params(|w| w.write_update(update))
+    }
+
+    /// render a `Delete` into a Sql string, values are inlined as literals.
+    /// An unfiltered delete is refused unless explicitly allowed.
+    pub fn delete(&self, delete: &Delete) -> SqlzResult<String> {
+        self.render(|w| w.write_delete(delete))
+    }
+
+    /// render a `Delete` into a Sql string with placeholders, and the bind
+    /// values in placeholder order
+    pub fn delete_with_params(&self, delete: &Delete) -> SqlzResult<(String, Vec<DataEnum>)> {
+        self.render_with_params(|w| w.write_delete(delete))
+    }
+
+    /// render a `TableCreate` into DDL statements, `Multiple` key columns
+    /// are indexed by additional statements
+    pub fn table_create(&self, table: &TableCreate) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_table_create(table))
+    }
+
+    /// render a `TableAlter` into DDL statements, one for each alter case
+    pub fn table_alter(&self, alter: &TableAlter) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_table_alter(alter))
+    }
+
+    pub fn table_rename(&self, rename: &TableRename) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_table_rename(rename))
+    }
+
+    pub fn table_drop(&self, drop: &TableDrop) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_table_drop(drop))
+    }
+
+    pub fn table_truncate(&self, truncate: &TableTruncate) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_table_truncate(truncate))
+    }
+
+    pub fn index_create(&self, index: &IndexCreate) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_index_create(index))
+    }
+
+    pub fn index_drop(&self, drop: &IndexDrop) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_index_drop(drop))
+    }
+
+    pub fn foreign_key_create(&self, foreign_key: &ForeignKeyCreate) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_foreign_key_create(foreign_key))
+    }
+
+    pub fn foreign_key_drop(&self, drop: &ForeignKeyDrop) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_foreign_key_drop(drop))
+    }
+
+    pub fn view_create(&self, view: &ViewCreate) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_view_create(view))
+    }
+
+    pub fn view_drop(&self, drop: &ViewDrop) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_view_drop(drop))
+    }
+
+    /// Postgres only
+    pub fn view_refresh(&self, refresh: &ViewRefresh) -> SqlzResult<Vec<String>> {
+        self.render_statements(|w| w.write_view_refresh(refresh))
+    }
+
+    /// render migration steps into DDL statements, in the order of the steps
+    pub fn migration(&self, plan: &[Migration]) -> SqlzResult<Vec<String>> {
+        let mut statements = vec![];
+        for step in plan.iter() {
+            let s = match step {
+                Migration::TableCreate(t) => self.table_create(t),
+                Migration::TableAlter(t) => self.table_alter(t),
+                Migration::TableDrop(t) => self.table_drop(t),
+                Migration::IndexCreate(i) => self.index_create(i),
+                Migration::IndexDrop(i) => self.index_drop(i),
+                Migration::ForeignKeyCreate(f) => self.foreign_key_create(f),
+                Migration::ForeignKeyDrop(f) => self.foreign_key_drop(f),
+                Migration::ViewCreate(v) => self.view_create(v),
+                Migration::ViewDrop(v) => self.view_drop(v),
+            }?;
+            statements.extend(s);
+        }
+        Ok(statements)
+    }
+}
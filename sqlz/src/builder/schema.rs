@@ -0,0 +1,884 @@
+use super::writer::SqlWriter;
+use super::Dialect;
+use crate::filter::validate_filter;
+use crate::{
+    Column, ColumnAlterCase, ColumnDefault, ColumnKey, ColumnType, ForeignKeyCreate,
+    ForeignKeyDrop, IndexCreate, IndexDrop, SqlzError, SqlzResult, TableAlter, TableCreate,
+    TableDrop, TableRename, TableTruncate, ViewCreate, ViewDrop, ViewRefresh,
+};
+
+impl SqlWriter {
+    /// `"name" TYPE [NOT NULL] [DEFAULT ...] [PRIMARY KEY | UNIQUE]`, with
+    /// auto increment in dialect specific form and MySql `COMMENT`. Only `key`
+    /// is written, which can differ from the column's key, e.g. a part of
+    /// composite primary key.
+    fn write_column_def(&mut self, column: &Column, key: Option<&ColumnKey>) -> SqlzResult<()> {
+        let auto_increment = column.auto_increment == Some(true);
+        let primary = key == Some(&ColumnKey::Primary);
+        if auto_increment {
+            if column.default.is_some() {
+                return Err(SqlzError::InvalidValue(format!(
+                    "auto increment column {} with default value",
+                    column.name
+                )));
+            }
+            let integer = matches!(
+                column.col_type,
+                ColumnType::SmallInt | ColumnType::Int | ColumnType::BigInt
+            );
+            if !integer {
+                return Err(SqlzError::InvalidValue(format!(
+                    "auto increment column {} is not an integer",
+                    column.name
+                )));
+            }
+            // only `INTEGER PRIMARY KEY` can be auto increment in Sqlite
+            let sqlite_rowid = primary && column.col_type == ColumnType::Int;
+            if self.dialect == Dialect::Sqlite && !sqlite_rowid {
+                return Err(SqlzError::Unsupported(format!(
+                    "auto increment column {} in Sqlite other than integer primary key",
+                    column.name
+                )));
+            }
+        }
+
+        self.push_ident(&column.name);
+        self.push(" ");
+        let col_type = self.dialect.column_type(&column.col_type)?;
+        self.push(&col_type);
+        if auto_increment && self.dialect == Dialect::Postgres {
+            self.push(" GENERATED BY DEFAULT AS IDENTITY");
+        }
+        if column.null == Some(false) {
+            self.push(" NOT NULL");
+        }
+        if let Some(default) = &column.default {
+            self.push(" DEFAULT ");
+            self.write_column_default(default)?;
+        }
+        if auto_increment && self.dialect == Dialect::MySql {
+            self.push(" AUTO_INCREMENT");
+        }
+        match key {
+            Some(ColumnKey::Primary) => self.push(" PRIMARY KEY"),
+            Some(ColumnKey::Unique) => self.push(" UNIQUE"),
+            _ => {}
+        }
+        if auto_increment && self.dialect == Dialect::Sqlite {
+            self.push(" AUTOINCREMENT");
+        }
+        if let (Some(comment), Dialect::MySql) = (&column.comment, self.dialect) {
+            self.push(" COMMENT ");
+            self.push(&self.dialect.quote_string(comment));
+        }
+        Ok(())
+    }
+
+    /// Postgres `COMMENT ON COLUMN` statement, MySql comments are inline and
+    /// Sqlite has none. With `clear`, a missing comment is set to `NULL`.
+    fn write_column_comment(&mut self, table: &str, column: &Column, clear: bool) {
+        if self.dialect != Dialect::Postgres || (column.comment.is_none() && !clear) {
+            return;
+        }
+        self.push("COMMENT ON COLUMN ");
+        self.push_ident(table);
+        self.push(".");
+        self.push_ident(&column.name);
+        self.push(" IS ");
+        match &column.comment {
+            Some(comment) => self.push(&self.dialect.quote_string(comment)),
+            None => self.push("NULL"),
+        }
+        self.end_statement();
+    }
+
+    /// MySql only accepts date and time functions as expression defaults
+    fn write_column_default(&mut self, default: &ColumnDefault) -> SqlzResult<()> {
+        match (default, self.dialect) {
+            (ColumnDefault::Value(v), _) => return self.push_value(v),
+            (ColumnDefault::CurrentTimestamp, _) => self.push("CURRENT_TIMESTAMP"),
+            (ColumnDefault::CurrentDate, Dialect::MySql) => self.push("(CURRENT_DATE)"),
+            (ColumnDefault::CurrentDate, _) => self.push("CURRENT_DATE"),
+            (ColumnDefault::CurrentTime, Dialect::MySql) => self.push("(CURRENT_TIME)"),
+            (ColumnDefault::CurrentTime, _) => self.push("CURRENT_TIME"),
+        }
+        Ok(())
+    }
+
+    fn write_ident_list<S: AsRef<str>>(&mut self, idents: &[S]) {
+        self.push("(");
+        for (i, ident) in idents.iter().enumerate() {
+            if i > 0 {
+                self.push(", ");
+            }
+            self.push_ident(ident.as_ref());
+        }
+        self.push(")");
+    }
+
+    fn write_foreign_key_def(&mut self, foreign_key: &ForeignKeyCreate) -> SqlzResult<()> {
+        let (from, to) = (&foreign_key.from.columns, &foreign_key.to.columns);
+        if from.is_empty() || from.len() != to.len() {
+            return Err(SqlzError::InvalidValue(format!(
+                "foreign key {} refers {} columns by {} columns",
+                foreign_key.name,
+                to.len(),
+                from.len()
+            )));
+        }
+        self.push("CONSTRAINT ");
+        self.push_ident(&foreign_key.name);
+        self.push(" FOREIGN KEY ");
+        self.write_ident_list(from);
+        self.push(" REFERENCES ");
+        self.push_ident(&foreign_key.to.table);
+        self.push(" ");
+        self.write_ident_list(to);
+        let on_delete = self.dialect.foreign_key_action(&foreign_key.on_delete)?;
+        let on_update = self.dialect.foreign_key_action(&foreign_key.on_update)?;
+        self.push(&format!(" ON DELETE {} ON UPDATE {}", on_delete, on_update));
+        Ok(())
+    }
+
+    /// a single primary key is written inline, composite primary key is
+    /// written as a table constraint. `Multiple` key columns get an index.
+    /// Comments are left out in Sqlite.
+    pub(crate) fn write_table_create(&mut self, table: &TableCreate) -> SqlzResult<()> {
+        if table.columns.is_empty() {
+            return Err(SqlzError::InvalidValue(format!(
+                "table {} without columns",
+                table.name
+            )));
+        }
+        let primary = table.primary_key_columns();
+        if let Some(c) = primary
+            .iter()
+            .find(|p| !table.columns.iter().any(|c| &c.name == *p))
+        {
+            return Err(SqlzError::InvalidValue(format!(
+                "primary key column {} not in table {}",
+                c, table.name
+            )));
+        }
+
+        self.push("CREATE TABLE ");
+        self.push_ident(&table.name);
+        self.push(" (");
+        self.push_list(&table.columns, ", ", |w, c| {
+            let key = if primary.len() == 1 && primary[0] == c.name {
+                Some(&ColumnKey::Primary)
+            } else if c.key == Some(ColumnKey::Unique) {
+                Some(&ColumnKey::Unique)
+            } else {
+                None
+            };
+            w.write_column_def(c, key)
+        })?;
+        if primary.len() > 1 {
+            self.push(", PRIMARY KEY ");
+            self.write_ident_list(&primary);
+        }
+        for u in table.unique_keys.iter().flatten() {
+            self.push(", CONSTRAINT ");
+            self.push_ident(&u.name);
+            self.push(" UNIQUE ");
+            self.write_ident_list(&u.columns);
+        }
+        for (i, c) in table.checks.iter().flatten().enumerate() {
+            validate_filter(&c.condition, &format!("/checks/{}/condition", i))?;
+            self.push(", CONSTRAINT ");
+            self.push_ident(&c.name);
+            self.push(" CHECK (");
+            self.write_expressions(&c.condition)?;
+            self.push(")");
+        }
+        for fk in table.foreign_keys.iter() {
+            if fk.from.table != table.name {
+                return Err(SqlzError::InvalidValue(format!(
+                    "foreign key {} does not belong to table {}",
+                    fk.name, table.name
+                )));
+            }
+            self.push(", ");
+            self.write_foreign_key_def(fk)?;
+        }
+        self.push(")");
+        if let Some(comment) = &table.comment {
+            match self.dialect {
+                Dialect::MySql => {
+                    self.push(" COMMENT = ");
+                    self.push(&self.dialect.quote_string(comment));
+                }
+                Dialect::Postgres => {
+                    self.end_statement();
+                    self.push("COMMENT ON TABLE ");
+                    self.push_ident(&table.name);
+                    self.push(" IS ");
+                    self.push(&self.dialect.quote_string(comment));
+                }
+                Dialect::Sqlite => {}
+            }
+        }
+        self.end_statement();
+        for c in table.columns.iter() {
+            self.write_column_comment(&table.name, c, false);
+        }
+
+        for c in table
+            .columns
+            .iter()
+            .filter(|c| c.key == Some(ColumnKey::Multiple))
+        {
+            self.push("CREATE INDEX ");
+            self.push_ident(&format!("{}_{}_index", table.name, c.name));
+            self.push(" ON ");
+            self.push_ident(&table.name);
+            self.push(" ");
+            self.write_ident_list(&[&c.name]);
+            self.end_statement();
+        }
+
+        Ok(())
+    }
+
+    /// one statement per alter case, and a comment statement in Postgres.
+    /// A modified column is the full target state of its type, nullability,
+    /// default value and comment: `null` other than `Some(false)` is nullable
+    /// and a missing default is dropped. So is auto increment, except that
+    /// modifying an auto increment column is `Unsupported` in Postgres, where
+    /// an identity is dropped if the column is not auto increment. Keys are
+    /// left untouched.
+    pub(crate) fn write_table_alter(&mut self, alter: &TableAlter) -> SqlzResult<()> {
+        for case in alter.alter.iter() {
+            self.push("ALTER TABLE ");
+            self.push_ident(&alter.name);
+            match case {
+                ColumnAlterCase::Add(c) => {
+                    let keyed = matches!(c.key, Some(ColumnKey::Primary | ColumnKey::Unique));
+                    if keyed && self.dialect == Dialect::Sqlite {
+                        return Err(SqlzError::Unsupported(
+                            "adding a primary or unique column in Sqlite".to_owned(),
+                        ));
+                    }
+                    self.push(" ADD COLUMN ");
+                    self.write_column_def(c, c.key.as_ref())?;
+                }
+                ColumnAlterCase::Modify(c) => match self.dialect {
+                    Dialect::MySql => {
+                        self.push(" MODIFY COLUMN ");
+                        self.write_column_def(c, None)?;
+                    }
+                    // an identity can not be kept or added without knowing the
+                    // current column, and adding one restarts its sequence
+                    Dialect::Postgres if c.auto_increment == Some(true) => {
+                        return Err(SqlzError::Unsupported(
+                            "modifying an auto increment column in Postgres".to_owned(),
+                        ))
+                    }
+                    Dialect::Postgres => {
+                        let col_type = self.dialect.column_type(&c.col_type)?;
+                        self.push(" ALTER COLUMN ");
+                        self.push_ident(&c.name);
+                        self.push(" TYPE ");
+                        self.push(&col_type);
+                        self.push(", ALTER COLUMN ");
+                        self.push_ident(&c.name);
+                        self.push(" DROP IDENTITY IF EXISTS");
+                        self.push(", ALTER COLUMN ");
+                        self.push_ident(&c.name);
+                        if c.null == Some(false) {
+                            self.push(" SET NOT NULL");
+                        } else {
+                            self.push(" DROP NOT NULL");
+                        }
+                        match &c.default {
+                            Some(default) => {
+                                self.push(", ALTER COLUMN ");
+                                self.push_ident(&c.name);
+                                self.push(" SET DEFAULT ");
+                                self.write_column_default(default)?;
+                            }
+                            None => {
+                                self.push(", ALTER COLUMN ");
+                                self.push_ident(&c.name);
+                                self.push(" DROP DEFAULT");
+                            }
+                        }
+                    }
+                    Dialect::Sqlite => {
+                        return Err(SqlzError::Unsupported(
+                            "modifying a column in Sqlite".to_owned(),
+                        ))
+                    }
+                },
+                ColumnAlterCase::Rename(c) => {
+                    self.push(" RENAME COLUMN ");
+                    self.push_ident(&c.from_name);
+                    self.push(" TO ");
+                    self.push_ident(&c.to_name);
+                }
+                ColumnAlterCase::Drop(c) => {
+                    self.push(" DROP COLUMN ");
+                    self.push_ident(&c.name);
+                }
+            }
+            self.end_statement();
+            match case {
+                ColumnAlterCase::Add(c) => self.write_column_comment(&alter.name, c, false),
+                ColumnAlterCase::Modify(c) => self.write_column_comment(&alter.name, c, true),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_table_rename(&mut self, rename: &TableRename) -> SqlzResult<()> {
+        self.push("ALTER TABLE ");
+        self.push_ident(&rename.from);
+        self.push(" RENAME TO ");
+        self.push_ident(&rename.to);
+        Ok(())
+    }
+
+    pub(crate) fn write_table_drop(&mut self, drop: &TableDrop) -> SqlzResult<()> {
+        self.push("DROP TABLE ");
+        self.push_ident(&drop.name);
+        Ok(())
+    }
+
+    /// Sqlite has no `TRUNCATE`, an unfiltered delete is used instead
+    pub(crate) fn write_table_truncate(&mut self, truncate: &TableTruncate) -> SqlzResult<()> {
+        match self.dialect {
+            Dialect::Sqlite => self.push("DELETE FROM "),
+            _ => self.push("TRUNCATE TABLE "),
+        }
+        self.push_ident(&truncate.name);
+        Ok(())
+    }
+
+    pub(crate) fn write_index_create(&mut self, index: &IndexCreate) -> SqlzResult<()> {
+        if index.columns.is_empty() {
+            return Err(SqlzError::InvalidValue(format!(
+                "index {} without columns",
+                index.name
+            )));
+        }
+        self.push("CREATE INDEX ");
+        self.push_ident(&index.name);
+        self.push(" ON ");
+        self.push_ident(&index.table);
+        self.push(" (");
+        self.write_order_list(&index.columns);
+        self.push(")");
+        Ok(())
+    }
+
+    pub(crate) fn write_index_drop(&mut self, drop: &IndexDrop) -> SqlzResult<()> {
+        self.push("DROP INDEX ");
+        self.push_ident(&drop.name);
+        if self.dialect == Dialect::MySql {
+            self.push(" ON ");
+            self.push_ident(&drop.table);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_foreign_key_create(&mut self, fk: &ForeignKeyCreate) -> SqlzResult<()> {
+        if self.dialect == Dialect::Sqlite {
+            return Err(SqlzError::Unsupported(
+                "adding a foreign key in Sqlite".to_owned(),
+            ));
+        }
+        self.push("ALTER TABLE ");
+        self.push_ident(&fk.from.table);
+        self.push(" ADD ");
+        self.write_foreign_key_def(fk)
+    }
+
+    pub(crate) fn write_foreign_key_drop(&mut self, drop: &ForeignKeyDrop) -> SqlzResult<()> {
+        self.push("ALTER TABLE ");
+        self.push_ident(&drop.table);
+        match self.dialect {
+            Dialect::Postgres => self.push(" DROP CONSTRAINT "),
+            Dialect::MySql => self.push(" DROP FOREIGN KEY "),
+            Dialect::Sqlite => {
+                return Err(SqlzError::Unsupported(
+                    "dropping a foreign key in Sqlite".to_owned(),
+                ))
+            }
+        }
+        self.push_ident(&drop.name);
+        Ok(())
+    }
+
+    fn materialized_view(&mut self, view: &str) -> SqlzResult<()> {
+        if self.dialect != Dialect::Postgres {
+            return Err(SqlzError::Unsupported(format!(
+                "materialized view {} in {:?}",
+                view, self.dialect
+            )));
+        }
+        self.push("MATERIALIZED ");
+        Ok(())
+    }
+
+    /// values of the select are written inline
+    pub(crate) fn write_view_create(&mut self, view: &ViewCreate) -> SqlzResult<()> {
+        self.push("CREATE ");
+        if view.materialized == Some(true) {
+            self.materialized_view(&view.name)?;
+        }
+        self.push("VIEW ");
+        self.push_ident(&view.name);
+        if let Some(columns) = &view.columns {
+            self.push(" ");
+            self.write_ident_list(columns);
+        }
+        self.push(" AS ");
+        self.write_select(&view.select)
+    }
+
+    pub(crate) fn write_view_drop(&mut self, drop: &ViewDrop) -> SqlzResult<()> {
+        self.push("DROP ");
+        if drop.materialized == Some(true) {
+            self.materialized_view(&drop.name)?;
+        }
+        self.push("VIEW ");
+        self.push_ident(&drop.name);
+        Ok(())
+    }
+
+    pub(crate) fn write_view_refresh(&mut self, refresh: &ViewRefresh) -> SqlzResult<()> {
+        self.push("REFRESH ");
+        self.materialized_view(&refresh.name)?;
+        self.push("VIEW ");
+        if refresh.concurrently == Some(true) {
+            self.push("CONCURRENTLY ");
+        }
+        self.push_ident(&refresh.name);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_schema_builder {
+    use crate::{
+        Dialect, ForeignKeyCreate, IndexCreate, IndexDrop, SqlBuilder, SqlzError, TableAlter,
+        TableCreate, TableTruncate, ViewCreate, ViewDrop, ViewRefresh,
+    };
+
+    const TABLE: &str = r#"
+    {
+        "name": "sqlz",
+        "columns": [
+            {"name": "id", "col_type": "Int", "null": false, "key": "Primary"},
+            {"name": "name", "col_type": "VarChar", "null": false, "key": "Unique"},
+            {"name": "tag", "col_type": "Text", "null": true, "key": "Multiple"},
+            {"name": "parent_id", "col_type": "Int", "null": null, "key": null}
+        ],
+        "foreign_key": {
+            "name": "fk_sqlz_parent",
+            "from": {"table": "sqlz", "column": "parent_id"},
+            "to": {"table": "sqlz_parent", "column": "id"},
+            "on_delete": "Cascade",
+            "on_update": "NoAction"
+        }
+    }"#;
+
+    #[test]
+    fn table_create() {
+        let table: TableCreate = serde_json::from_str(TABLE).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_create(&table)
+                .unwrap(),
+            vec![
+                r#"CREATE TABLE "sqlz" ("id" INTEGER NOT NULL PRIMARY KEY, "name" VARCHAR NOT NULL UNIQUE, "tag" TEXT, "parent_id" INTEGER, CONSTRAINT "fk_sqlz_parent" FOREIGN KEY ("parent_id") REFERENCES "sqlz_parent" ("id") ON DELETE CASCADE ON UPDATE NO ACTION)"#,
+                r#"CREATE INDEX "sqlz_tag_index" ON "sqlz" ("tag")"#,
+            ]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_create(&table).unwrap(),
+            vec![
+                "CREATE TABLE `sqlz` (`id` INT NOT NULL PRIMARY KEY, `name` VARCHAR(255) NOT NULL UNIQUE, `tag` TEXT, `parent_id` INT, CONSTRAINT `fk_sqlz_parent` FOREIGN KEY (`parent_id`) REFERENCES `sqlz_parent` (`id`) ON DELETE CASCADE ON UPDATE NO ACTION)",
+                "CREATE INDEX `sqlz_tag_index` ON `sqlz` (`tag`)",
+            ]
+        );
+    }
+
+    #[test]
+    fn table_comments() {
+        let json = r#"
+        {
+            "name": "t",
+            "columns": [
+                {"name": "id", "col_type": "Int", "null": false, "key": "Primary", "comment": "row's id"},
+                {"name": "name", "col_type": "Text", "null": null, "key": null}
+            ],
+            "comment": "a table"
+        }"#;
+        let table: TableCreate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_create(&table)
+                .unwrap(),
+            vec![
+                r#"CREATE TABLE "t" ("id" INTEGER NOT NULL PRIMARY KEY, "name" TEXT)"#,
+                r#"COMMENT ON TABLE "t" IS 'a table'"#,
+                r#"COMMENT ON COLUMN "t"."id" IS 'row''s id'"#,
+            ]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_create(&table).unwrap(),
+            vec!["CREATE TABLE `t` (`id` INT NOT NULL PRIMARY KEY COMMENT 'row''s id', `name` TEXT) COMMENT = 'a table'"]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite)
+                .table_create(&table)
+                .unwrap(),
+            vec![r#"CREATE TABLE "t" ("id" INTEGER NOT NULL PRIMARY KEY, "name" TEXT)"#]
+        );
+
+        let alter: TableAlter = serde_json::from_str(
+            r#"{"name": "t", "alter": [{"Add": {"name": "c", "col_type": "Int", "null": null, "key": null, "comment": "new"}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_alter(&alter)
+                .unwrap(),
+            vec![
+                r#"ALTER TABLE "t" ADD COLUMN "c" INTEGER"#,
+                r#"COMMENT ON COLUMN "t"."c" IS 'new'"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn table_create_composite_primary() {
+        let json = r#"
+        {
+            "name": "sqlz",
+            "columns": [
+                {"name": "a", "col_type": "Int", "null": false, "key": "Primary"},
+                {"name": "b", "col_type": "Json", "null": false, "key": "Primary"}
+            ],
+            "foreign_key": null
+        }"#;
+        let table: TableCreate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite)
+                .table_create(&table)
+                .unwrap(),
+            vec![
+                r#"CREATE TABLE "sqlz" ("a" INTEGER NOT NULL, "b" TEXT NOT NULL, PRIMARY KEY ("a", "b"))"#
+            ]
+        );
+    }
+
+    #[test]
+    fn table_create_constraints() {
+        let json = r#"
+        {
+            "name": "orders",
+            "columns": [
+                {"name": "id", "col_type": "BigInt", "null": false, "key": null, "auto_increment": true},
+                {"name": "shop", "col_type": "Int", "null": false, "key": null},
+                {"name": "code", "col_type": {"VarChar": 16}, "null": false, "key": null},
+                {"name": "qty", "col_type": "Int", "null": false, "key": null, "default": {"Value": 0}},
+                {"name": "created", "col_type": "DateTime", "null": null, "key": null, "default": "CurrentTimestamp"},
+                {"name": "item_shop", "col_type": "Int", "null": null, "key": null},
+                {"name": "item_code", "col_type": {"VarChar": 16}, "null": null, "key": null}
+            ],
+            "foreign_keys": [
+                {
+                    "name": "fk_orders_shop",
+                    "from": {"table": "orders", "columns": "shop"},
+                    "to": {"table": "shops", "columns": "id"},
+                    "on_delete": "Restrict",
+                    "on_update": "Cascade"
+                },
+                {
+                    "name": "fk_orders_item",
+                    "from": {"table": "orders", "columns": ["item_shop", "item_code"]},
+                    "to": {"table": "items", "columns": ["shop", "code"]},
+                    "on_delete": "SetNull",
+                    "on_update": "NoAction"
+                }
+            ],
+            "primary_key": ["id", "shop"],
+            "unique_keys": [{"name": "uk_orders_code", "columns": ["shop", "code"]}],
+            "checks": [{"name": "ck_orders_qty", "condition": [{"column": "qty", "equation": {"GreaterEqual": 0}}]}]
+        }"#;
+        let table: TableCreate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_create(&table)
+                .unwrap(),
+            vec![
+                r#"CREATE TABLE "orders" ("id" BIGINT GENERATED BY DEFAULT AS IDENTITY NOT NULL, "shop" INTEGER NOT NULL, "code" VARCHAR(16) NOT NULL, "qty" INTEGER NOT NULL DEFAULT 0, "created" TIMESTAMP DEFAULT CURRENT_TIMESTAMP, "item_shop" INTEGER, "item_code" VARCHAR(16), PRIMARY KEY ("id", "shop"), CONSTRAINT "uk_orders_code" UNIQUE ("shop", "code"), CONSTRAINT "ck_orders_qty" CHECK ("qty" >= 0), CONSTRAINT "fk_orders_shop" FOREIGN KEY ("shop") REFERENCES "shops" ("id") ON DELETE RESTRICT ON UPDATE CASCADE, CONSTRAINT "fk_orders_item" FOREIGN KEY ("item_shop", "item_code") REFERENCES "items" ("shop", "code") ON DELETE SET NULL ON UPDATE NO ACTION)"#
+            ]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_create(&table).unwrap(),
+            vec![
+                "CREATE TABLE `orders` (`id` BIGINT NOT NULL AUTO_INCREMENT, `shop` INT NOT NULL, `code` VARCHAR(16) NOT NULL, `qty` INT NOT NULL DEFAULT 0, `created` DATETIME DEFAULT CURRENT_TIMESTAMP, `item_shop` INT, `item_code` VARCHAR(16), PRIMARY KEY (`id`, `shop`), CONSTRAINT `uk_orders_code` UNIQUE (`shop`, `code`), CONSTRAINT `ck_orders_qty` CHECK (`qty` >= 0), CONSTRAINT `fk_orders_shop` FOREIGN KEY (`shop`) REFERENCES `shops` (`id`) ON DELETE RESTRICT ON UPDATE CASCADE, CONSTRAINT `fk_orders_item` FOREIGN KEY (`item_shop`, `item_code`) REFERENCES `items` (`shop`, `code`) ON DELETE SET NULL ON UPDATE NO ACTION)"
+            ]
+        );
+        // auto increment in Sqlite requires a single integer primary key
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Sqlite).table_create(&table),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn table_create_sqlite_autoincrement() {
+        let json = r#"
+        {
+            "name": "sqlz",
+            "columns": [
+                {"name": "id", "col_type": "Int", "null": null, "key": "Primary", "auto_increment": true},
+                {"name": "day", "col_type": "Date", "null": null, "key": null, "default": "CurrentDate"}
+            ]
+        }"#;
+        let table: TableCreate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite)
+                .table_create(&table)
+                .unwrap(),
+            vec![
+                r#"CREATE TABLE "sqlz" ("id" INTEGER PRIMARY KEY AUTOINCREMENT, "day" DATE DEFAULT CURRENT_DATE)"#
+            ]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_create(&table).unwrap(),
+            vec![
+                "CREATE TABLE `sqlz` (`id` INT AUTO_INCREMENT PRIMARY KEY, `day` DATE DEFAULT (CURRENT_DATE))"
+            ]
+        );
+    }
+
+    #[test]
+    fn table_alter() {
+        let json = r#"
+        {
+            "name": "sqlz",
+            "alter": [
+                {"Add": {"name": "score", "col_type": "Double", "null": null, "key": null}},
+                {"Modify": {"name": "score", "col_type": "Float", "null": false, "key": null}},
+                {"Rename": {"from_name": "score", "to_name": "point"}},
+                {"Drop": {"name": "tag"}}
+            ]
+        }"#;
+        let alter: TableAlter = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_alter(&alter)
+                .unwrap(),
+            vec![
+                r#"ALTER TABLE "sqlz" ADD COLUMN "score" DOUBLE PRECISION"#,
+                r#"ALTER TABLE "sqlz" ALTER COLUMN "score" TYPE REAL, ALTER COLUMN "score" DROP IDENTITY IF EXISTS, ALTER COLUMN "score" SET NOT NULL, ALTER COLUMN "score" DROP DEFAULT"#,
+                r#"COMMENT ON COLUMN "sqlz"."score" IS NULL"#,
+                r#"ALTER TABLE "sqlz" RENAME COLUMN "score" TO "point""#,
+                r#"ALTER TABLE "sqlz" DROP COLUMN "tag""#,
+            ]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_alter(&alter).unwrap(),
+            vec![
+                "ALTER TABLE `sqlz` ADD COLUMN `score` DOUBLE",
+                "ALTER TABLE `sqlz` MODIFY COLUMN `score` FLOAT NOT NULL",
+                "ALTER TABLE `sqlz` RENAME COLUMN `score` TO `point`",
+                "ALTER TABLE `sqlz` DROP COLUMN `tag`",
+            ]
+        );
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Sqlite).table_alter(&alter),
+            Err(SqlzError::Unsupported(_))
+        ));
+
+        // the modified column is the full target state, but Postgres can not
+        // modify an identity column
+        let json = r#"
+        {
+            "name": "sqlz",
+            "alter": [
+                {"Modify": {"name": "id", "col_type": "BigInt", "null": false, "key": null, "auto_increment": true}},
+                {"Modify": {"name": "tag", "col_type": "Text", "null": null, "key": null, "default": {"Value": "a"}}}
+            ]
+        }"#;
+        let mut alter: TableAlter = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_alter(&alter).unwrap(),
+            vec![
+                "ALTER TABLE `sqlz` MODIFY COLUMN `id` BIGINT NOT NULL AUTO_INCREMENT",
+                "ALTER TABLE `sqlz` MODIFY COLUMN `tag` TEXT DEFAULT 'a'",
+            ]
+        );
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Postgres).table_alter(&alter),
+            Err(SqlzError::Unsupported(_))
+        ));
+        alter.alter.remove(0);
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_alter(&alter)
+                .unwrap(),
+            vec![
+                r#"ALTER TABLE "sqlz" ALTER COLUMN "tag" TYPE TEXT, ALTER COLUMN "tag" DROP IDENTITY IF EXISTS, ALTER COLUMN "tag" DROP NOT NULL, ALTER COLUMN "tag" SET DEFAULT 'a'"#,
+                r#"COMMENT ON COLUMN "sqlz"."tag" IS NULL"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn index_and_foreign_key() {
+        let index: IndexCreate = serde_json::from_str(
+            r#"{"name": "idx", "table": "sqlz", "columns": [{"name": "a", "order": "Desc"}, {"name": "b", "order": null}]}"#,
+        )
+        .unwrap();
+        let drop: IndexDrop = serde_json::from_str(r#"{"name": "idx", "table": "sqlz"}"#).unwrap();
+        let fk: ForeignKeyCreate = serde_json::from_str(
+            r#"
+            {
+                "name": "fk",
+                "from": {"table": "sqlz", "column": "pid"},
+                "to": {"table": "sqlz_parent", "column": "id"},
+                "on_delete": "SetNull",
+                "on_update": "Restrict"
+            }"#,
+        )
+        .unwrap();
+
+        let pg = SqlBuilder::new(Dialect::Postgres);
+        assert_eq!(
+            pg.index_create(&index).unwrap(),
+            vec![r#"CREATE INDEX "idx" ON "sqlz" ("a" DESC, "b")"#]
+        );
+        assert_eq!(pg.index_drop(&drop).unwrap(), vec![r#"DROP INDEX "idx""#]);
+        assert_eq!(
+            pg.foreign_key_create(&fk).unwrap(),
+            vec![
+                r#"ALTER TABLE "sqlz" ADD CONSTRAINT "fk" FOREIGN KEY ("pid") REFERENCES "sqlz_parent" ("id") ON DELETE SET NULL ON UPDATE RESTRICT"#
+            ]
+        );
+        assert_eq!(
+            pg.foreign_key_drop(&drop).unwrap(),
+            vec![r#"ALTER TABLE "sqlz" DROP CONSTRAINT "idx""#]
+        );
+
+        let my = SqlBuilder::new(Dialect::MySql);
+        assert_eq!(
+            my.index_drop(&drop).unwrap(),
+            vec!["DROP INDEX `idx` ON `sqlz`"]
+        );
+        assert_eq!(
+            my.foreign_key_drop(&drop).unwrap(),
+            vec!["ALTER TABLE `sqlz` DROP FOREIGN KEY `idx`"]
+        );
+
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Sqlite).foreign_key_create(&fk),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn table_truncate() {
+        let truncate = TableTruncate {
+            name: "sqlz".to_owned(),
+        };
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_truncate(&truncate)
+                .unwrap(),
+            vec![r#"TRUNCATE TABLE "sqlz""#]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite)
+                .table_truncate(&truncate)
+                .unwrap(),
+            vec![r#"DELETE FROM "sqlz""#]
+        );
+    }
+
+    #[test]
+    fn view() {
+        let json = r#"
+        {
+            "name": "active_users",
+            "columns": ["id", "user_name"],
+            "select": {
+                "table": "users",
+                "columns": ["id", "name"],
+                "filter": [{"column": "status", "equation": {"Equal": "active"}}],
+                "order": null,
+                "limit": null,
+                "offset": null
+            }
+        }"#;
+        let mut view: ViewCreate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).view_create(&view).unwrap(),
+            vec!["CREATE VIEW `active_users` (`id`, `user_name`) AS SELECT `id`, `name` FROM `users` WHERE `status` = 'active'"]
+        );
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite).view_create(&view).unwrap(),
+            vec![
+                r#"CREATE VIEW "active_users" ("id", "user_name") AS SELECT "id", "name" FROM "users" WHERE "status" = 'active'"#
+            ]
+        );
+
+        view.materialized = Some(true);
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .view_create(&view)
+                .unwrap(),
+            vec![
+                r#"CREATE MATERIALIZED VIEW "active_users" ("id", "user_name") AS SELECT "id", "name" FROM "users" WHERE "status" = 'active'"#
+            ]
+        );
+        assert!(matches!(
+            SqlBuilder::new(Dialect::MySql).view_create(&view),
+            Err(SqlzError::Unsupported(_))
+        ));
+
+        let drop = ViewDrop {
+            name: "active_users".to_owned(),
+            materialized: Some(true),
+        };
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).view_drop(&drop).unwrap(),
+            vec![r#"DROP MATERIALIZED VIEW "active_users""#]
+        );
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Sqlite).view_drop(&drop),
+            Err(SqlzError::Unsupported(_))
+        ));
+
+        let refresh = ViewRefresh {
+            name: "active_users".to_owned(),
+            concurrently: Some(true),
+        };
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .view_refresh(&refresh)
+                .unwrap(),
+            vec![r#"REFRESH MATERIALIZED VIEW CONCURRENTLY "active_users""#]
+        );
+        assert!(matches!(
+            SqlBuilder::new(Dialect::MySql).view_refresh(&refresh),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+}
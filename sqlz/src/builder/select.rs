@@ -0,0 +1,883 @@
+use super::writer::SqlWriter;
+use super::Dialect;
+use crate::{
+    Aggregate, AggregateFunction, ColumnAlias, Comparison, Condition, Conjunction, DataEnum,
+    Equation, Expression, Join, JoinType, Order, OrderType, Select, SetOperation, SqlzError,
+    SqlzResult, TableSource,
+};
+
+impl SqlWriter {
+    pub(crate) fn write_select(&mut self, select: &Select) -> SqlzResult<()> {
+        select.check()?;
+        self.write_query(select)
+    }
+
+    /// a nested select in parentheses
+    fn write_subquery(&mut self, select: &Select) -> SqlzResult<()> {
+        // aggregate aliases of an outer `HAVING` are not in scope
+        let outer = std::mem::take(&mut self.aggregates);
+        self.push("(");
+        let res = self.write_query(select);
+        self.aggregates = outer;
+        res?;
+        self.push(")");
+        Ok(())
+    }
+
+    fn write_query(&mut self, select: &Select) -> SqlzResult<()> {
+        if let Some(with) = select.with.as_ref().filter(|w| !w.is_empty()) {
+            self.push("WITH ");
+            self.push_list(with, ", ", |w, cte| {
+                w.push_ident(&cte.name);
+                if let Some(columns) = cte.columns.as_ref().filter(|c| !c.is_empty()) {
+                    w.push(" (");
+                    w.push_list(columns, ", ", |w, c| {
+                        w.push_ident(c);
+                        Ok(())
+                    })?;
+                    w.push(")");
+                }
+                w.push(" AS ");
+                w.write_subquery(&cte.select)
+            })?;
+            self.push(" ");
+        }
+
+        self.write_select_body(select, &select.keyset_filter()?)?;
+
+        for op in select.set_operations.iter().flatten() {
+            let (keyword, operand) = match op {
+                SetOperation::Union(s) => (" UNION ", s),
+                SetOperation::UnionAll(s) => (" UNION ALL ", s),
+                SetOperation::Intersect(s) => (" INTERSECT ", s),
+                SetOperation::Except(s) => (" EXCEPT ", s),
+            };
+            // Sqlite does not accept parenthesized operands, so they are kept plain
+            if operand.with.is_some()
+                || operand.order.is_some()
+                || operand.limit.is_some()
+                || operand.offset.is_some()
+                || operand.set_operations.is_some()
+                || operand.cursor.is_some()
+            {
+                return Err(SqlzError::InvalidValue(
+                    "set operation on a select with `WITH`, `ORDER BY`, `LIMIT`, `OFFSET` or set operations".to_owned(),
+                ));
+            }
+            self.push(keyword);
+            self.write_select_body(operand, &operand.filter)?;
+        }
+
+        if let Some(order) = &select.order {
+            self.write_order(order);
+        }
+
+        self.write_limit_offset(select.limit, select.offset);
+
+        Ok(())
+    }
+
+    /// `SELECT` to `HAVING`
+    fn write_select_body(
+        &mut self,
+        select: &Select,
+        filter: &Option<Vec<Expression>>,
+    ) -> SqlzResult<()> {
+        self.push("SELECT ");
+        if select.columns.is_empty() {
+            self.push("*");
+        } else {
+            self.push_list(&select.columns, ", ", |w, c| w.write_column_alias(c))?;
+        }
+        self.push(" FROM ");
+        self.write_table_alias(&select.table, &select.alias)?;
+
+        for join in select.join.iter().flatten() {
+            self.write_join(join)?;
+        }
+
+        self.write_filter(filter)?;
+
+        if let Some(group_by) = select.group_by.as_ref().filter(|g| !g.is_empty()) {
+            self.push(" GROUP BY ");
+            self.push_list(group_by, ", ", |w, c| {
+                w.push_ident(c);
+                Ok(())
+            })?;
+        }
+
+        if let Some(having) = select.having.as_ref().filter(|h| !h.is_empty()) {
+            // output aliases are not visible in Postgres `HAVING`, so aggregate
+            // aliases are replaced by their aggregate expressions
+            let aggregates = select
+                .columns
+                .iter()
+                .filter_map(|c| match c {
+                    ColumnAlias::Aggregate(a) if a.alias.is_some() => Some(a.clone()),
+                    _ => None,
+                })
+                .collect();
+            let outer = std::mem::replace(&mut self.aggregates, aggregates);
+            self.push(" HAVING ");
+            let res = self.write_expressions(having);
+            self.aggregates = outer;
+            res?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn write_column_alias(&mut self, column: &ColumnAlias) -> SqlzResult<()> {
+        match column {
+            ColumnAlias::Simple(name) => self.push_ident(name),
+            ColumnAlias::Alias((name, alias)) => {
+                self.push_ident(name);
+                self.push(" AS ");
+                self.push_ident(alias);
+            }
+            ColumnAlias::Aggregate(a) => {
+                self.write_aggregate(a)?;
+                if let Some(alias) = &a.alias {
+                    self.push(" AS ");
+                    self.push_ident(alias);
+                }
+            }
+            ColumnAlias::Computed(c) => {
+                self.write_column_expr(&c.expr)?;
+                if let Some(alias) = &c.alias {
+                    self.push(" AS ");
+                    self.push_ident(alias);
+                }
+            }
+            ColumnAlias::Window(w) => {
+                self.write_window(w)?;
+                if let Some(alias) = &w.alias {
+                    self.push(" AS ");
+                    self.push_ident(alias);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_aggregate(&mut self, aggregate: &Aggregate) -> SqlzResult<()> {
+        let function = match aggregate.function {
+            AggregateFunction::Count => "COUNT(",
+            AggregateFunction::CountDistinct if aggregate.column == "*" => {
+                return Err(SqlzError::InvalidValue("count distinct on `*`".to_owned()))
+            }
+            AggregateFunction::CountDistinct => "COUNT(DISTINCT ",
+            AggregateFunction::Sum => "SUM(",
+            AggregateFunction::Avg => "AVG(",
+            AggregateFunction::Min => "MIN(",
+            AggregateFunction::Max => "MAX(",
+        };
+        self.push(function);
+        self.push_ident(&aggregate.column);
+        self.push(")");
+        Ok(())
+    }
+
+    /// column of a condition, which can be an aggregate alias in `HAVING`
+    fn write_condition_column(&mut self, column: &str) -> SqlzResult<()> {
+        let aggregate = self
+            .aggregates
+            .iter()
+            .find(|a| a.alias.as_deref() == Some(column))
+            .cloned();
+        match aggregate {
+            Some(a) => self.write_aggregate(&a),
+            None => {
+                self.push_ident(column);
+                Ok(())
+            }
+        }
+    }
+
+    /// a derived table needs an alias in Postgres and MySql
+    fn write_table_alias(&mut self, table: &TableSource, alias: &Option<String>) -> SqlzResult<()> {
+        match table {
+            TableSource::Table(t) => self.push_ident(t),
+            TableSource::Derived(_) if alias.is_none() => {
+                return Err(SqlzError::InvalidValue(
+                    "derived table without alias".to_owned(),
+                ))
+            }
+            TableSource::Derived(d) => self.write_subquery(&d.select)?,
+        }
+        if let Some(a) = alias {
+            self.push(" AS ");
+            self.push_ident(a);
+        }
+        Ok(())
+    }
+
+    fn write_join(&mut self, join: &Join) -> SqlzResult<()> {
+        let on = join.on.as_deref().unwrap_or_default();
+        let keyword = match join.join_type {
+            JoinType::Inner => " INNER JOIN ",
+            JoinType::Left => " LEFT JOIN ",
+            JoinType::Right => " RIGHT JOIN ",
+            JoinType::Full if self.dialect == Dialect::MySql => {
+                return Err(SqlzError::Unsupported("`FULL JOIN` in MySql".to_owned()))
+            }
+            JoinType::Full => " FULL JOIN ",
+            JoinType::Cross => " CROSS JOIN ",
+        };
+        match (&join.join_type, on.is_empty()) {
+            (JoinType::Cross, false) => {
+                return Err(SqlzError::InvalidValue(format!(
+                    "cross join {} with `ON` condition",
+                    join.table
+                )))
+            }
+            (JoinType::Cross, true) | (_, false) => {}
+            (_, true) => {
+                return Err(SqlzError::InvalidValue(format!(
+                    "join {} without `ON` condition",
+                    join.table
+                )))
+            }
+        }
+
+        self.push(keyword);
+        self.write_table_alias(&join.table, &join.alias)?;
+        if !on.is_empty() {
+            self.push(" ON ");
+            self.write_expressions(on)?;
+        }
+        Ok(())
+    }
+
+    /// write ` WHERE ...` if the filter is not empty
+    pub(crate) fn write_filter(&mut self, filter: &Option<Vec<Expression>>) -> SqlzResult<()> {
+        match filter {
+            Some(exprs) if !exprs.is_empty() => {
+                self.push(" WHERE ");
+                self.write_expressions(exprs)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn write_expressions(&mut self, exprs: &[Expression]) -> SqlzResult<()> {
+        self.push_list(exprs, " ", |w, e| match e {
+            Expression::Conjunction(c) => {
+                w.push(match c {
+                    Conjunction::AND => "AND",
+                    Conjunction::OR => "OR",
+                });
+                Ok(())
+            }
+            Expression::Simple(c) => w.write_condition(c),
+            Expression::Nest(v) => {
+                w.push("(");
+                w.write_expressions(v)?;
+                w.push(")");
+                Ok(())
+            }
+            Expression::Not(n) => {
+                w.push("NOT (");
+                w.write_expressions(&n.not)?;
+                w.push(")");
+                Ok(())
+            }
+            Expression::Exists(e) => {
+                w.push("EXISTS ");
+                w.write_subquery(&e.exists)
+            }
+        })
+    }
+
+    /// comparing with `NULL` is written as `IS NULL` or `IS NOT NULL`
+    pub(crate) fn write_condition(&mut self, condition: &Condition) -> SqlzResult<()> {
+        let (column, equation) = (&condition.column, &condition.equation);
+        match equation {
+            Equation::In(v) if v.is_empty() => {
+                // `IN ()` is not valid Sql, an empty set matches nothing
+                self.push("1 = 0");
+                return Ok(());
+            }
+            Equation::NotIn(v) if v.is_empty() => {
+                self.push("1 = 1");
+                return Ok(());
+            }
+            Equation::Regex(_) | Equation::NotRegex(_) if self.dialect == Dialect::Sqlite => {
+                return Err(SqlzError::Unsupported("`REGEXP` in Sqlite".to_owned()));
+            }
+            Equation::ILike(_) | Equation::NotILike(_) if self.dialect != Dialect::Postgres => {
+                // case insensitive like is only native in Postgres
+                self.push("LOWER(");
+                self.write_condition_column(column)?;
+                self.push(")");
+                self.push(match equation {
+                    Equation::ILike(_) => " LIKE LOWER(",
+                    _ => " NOT LIKE LOWER(",
+                });
+                if let Equation::ILike(p) | Equation::NotILike(p) = equation {
+                    self.push_value(&p.as_str().into())?;
+                }
+                self.push(")");
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        self.write_condition_column(column)?;
+        match equation {
+            Equation::Equal(DataEnum::Null) | Equation::IsNull => {
+                self.push(" IS NULL");
+                Ok(())
+            }
+            Equation::NotEqual(DataEnum::Null) | Equation::IsNotNull => {
+                self.push(" IS NOT NULL");
+                Ok(())
+            }
+            Equation::Equal(v) => self.write_operator_value(" = ", v),
+            Equation::NotEqual(v) => self.write_operator_value(" <> ", v),
+            Equation::Greater(v) => self.write_operator_value(" > ", v),
+            Equation::GreaterEqual(v) => self.write_operator_value(" >= ", v),
+            Equation::Less(v) => self.write_operator_value(" < ", v),
+            Equation::LessEqual(v) => self.write_operator_value(" <= ", v),
+            Equation::In(v) | Equation::NotIn(v) => {
+                self.push(match equation {
+                    Equation::In(_) => " IN (",
+                    _ => " NOT IN (",
+                });
+                self.push_list(v, ", ", |w, d| w.push_value(d))?;
+                self.push(")");
+                Ok(())
+            }
+            Equation::Between((l, r)) | Equation::NotBetween((l, r)) => {
+                self.push(match equation {
+                    Equation::Between(_) => " BETWEEN ",
+                    _ => " NOT BETWEEN ",
+                });
+                self.push_value(l)?;
+                self.push(" AND ");
+                self.push_value(r)
+            }
+            Equation::Like(p) => self.write_operator_value(" LIKE ", &p.as_str().into()),
+            Equation::NotLike(p) => self.write_operator_value(" NOT LIKE ", &p.as_str().into()),
+            Equation::ILike(p) => self.write_operator_value(" ILIKE ", &p.as_str().into()),
+            Equation::NotILike(p) => self.write_operator_value(" NOT ILIKE ", &p.as_str().into()),
+            Equation::Regex(p) | Equation::NotRegex(p) => {
+                let op = match (equation, self.dialect) {
+                    (Equation::Regex(_), Dialect::Postgres) => " ~ ",
+                    (_, Dialect::Postgres) => " !~ ",
+                    (Equation::Regex(_), _) => " REGEXP ",
+                    _ => " NOT REGEXP ",
+                };
+                self.write_operator_value(op, &p.as_str().into())
+            }
+            Equation::Column((c, other)) => {
+                self.push(comparison_op(c));
+                self.write_condition_column(other)
+            }
+            Equation::InSelect(s) => {
+                self.push(" IN ");
+                self.write_subquery(s)
+            }
+            Equation::NotInSelect(s) => {
+                self.push(" NOT IN ");
+                self.write_subquery(s)
+            }
+        }
+    }
+
+    fn write_operator_value(&mut self, op: &str, value: &DataEnum) -> SqlzResult<()> {
+        self.push(op);
+        self.push_value(value)
+    }
+
+    pub(crate) fn write_order(&mut self, order: &[Order]) {
+        if order.is_empty() {
+            return;
+        }
+        self.push(" ORDER BY ");
+        self.write_order_list(order);
+    }
+
+    /// `"c1" ASC, "c2" DESC, ...`
+    pub(crate) fn write_order_list(&mut self, order: &[Order]) {
+        for (i, o) in order.iter().enumerate() {
+            if i > 0 {
+                self.push(", ");
+            }
+            self.push_ident(&o.name);
+            match o.order {
+                Some(OrderType::Asc) => self.push(" ASC"),
+                Some(OrderType::Desc) => self.push(" DESC"),
+                None => {}
+            }
+        }
+    }
+
+    /// MySql and Sqlite do not accept `OFFSET` without `LIMIT`
+    pub(crate) fn write_limit_offset(&mut self, limit: Option<u64>, offset: Option<u64>) {
+        match (limit, offset) {
+            (Some(l), _) => self.push(&format!(" LIMIT {}", l)),
+            (None, Some(_)) => match self.dialect {
+                Dialect::MySql => self.push(&format!(" LIMIT {}", u64::MAX)),
+                Dialect::Sqlite => self.push(" LIMIT -1"),
+                Dialect::Postgres => {}
+            },
+            (None, None) => {}
+        }
+        if let Some(o) = offset {
+            self.push(&format!(" OFFSET {}", o));
+        }
+    }
+}
+
+fn comparison_op(comparison: &Comparison) -> &'static str {
+    match comparison {
+        Comparison::Equal => " = ",
+        Comparison::NotEqual => " <> ",
+        Comparison::Greater => " > ",
+        Comparison::GreaterEqual => " >= ",
+        Comparison::Less => " < ",
+        Comparison::LessEqual => " <= ",
+    }
+}
+
+#[cfg(test)]
+mod tests_select_builder {
+    use crate::{
+        Conjunction, DataEnum, Dialect, Expression, FilterError, FilterErrorKind, Select,
+        SetOperation, SqlBuilder, SqlzError,
+    };
+
+    const SELECT: &str = r##"
+    {
+        "table": "sqlz",
+        "columns":["c1",["c2","c2_t"]],
+        "filter":[
+            {"column":"c1","equation":{"Between":[23,25]}},
+            "OR",
+            {"column":"c2","equation":{"Equal":1}},
+            "AND",
+            [
+                {"column":"c3","equation":{"Greater":23}},
+                "AND",
+                {"column":"c4","equation":{"In":["T1","T2"]}}
+            ]
+        ],
+        "order":null,
+        "limit":10,
+        "offset":20
+    }"##;
+
+    fn render(dialect: Dialect, json: &str) -> String {
+        let select: Select = serde_json::from_str(json).unwrap();
+        SqlBuilder::new(dialect).select(&select).unwrap()
+    }
+
+    #[test]
+    fn select_postgres() {
+        assert_eq!(
+            render(Dialect::Postgres, SELECT),
+            r#"SELECT "c1", "c2" AS "c2_t" FROM "sqlz" WHERE "c1" BETWEEN 23 AND 25 OR "c2" = 1 AND ("c3" > 23 AND "c4" IN ('T1', 'T2')) LIMIT 10 OFFSET 20"#
+        );
+    }
+
+    #[test]
+    fn select_mysql() {
+        assert_eq!(
+            render(Dialect::MySql, SELECT),
+            "SELECT `c1`, `c2` AS `c2_t` FROM `sqlz` WHERE `c1` BETWEEN 23 AND 25 OR `c2` = 1 AND (`c3` > 23 AND `c4` IN ('T1', 'T2')) LIMIT 10 OFFSET 20"
+        );
+    }
+
+    #[test]
+    fn select_sqlite() {
+        assert_eq!(
+            render(Dialect::Sqlite, SELECT),
+            r#"SELECT "c1", "c2" AS "c2_t" FROM "sqlz" WHERE "c1" BETWEEN 23 AND 25 OR "c2" = 1 AND ("c3" > 23 AND "c4" IN ('T1', 'T2')) LIMIT 10 OFFSET 20"#
+        );
+    }
+
+    #[test]
+    fn select_order_offset_escape() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": [],
+            "filter": [{"column":"name","equation":{"Like":"O'Neil\\%"}}],
+            "order": [{"name":"c1","order":"Desc"},{"name":"c2","order":null}],
+            "limit": null,
+            "offset": 5
+        }"#;
+
+        assert_eq!(
+            render(Dialect::Postgres, json),
+            r#"SELECT * FROM "sqlz" WHERE "name" LIKE 'O''Neil\%' ORDER BY "c1" DESC, "c2" OFFSET 5"#
+        );
+        assert_eq!(
+            render(Dialect::MySql, json),
+            r"SELECT * FROM `sqlz` WHERE `name` LIKE 'O''Neil\\%' ORDER BY `c1` DESC, `c2` LIMIT 18446744073709551615 OFFSET 5"
+        );
+        assert_eq!(
+            render(Dialect::Sqlite, json),
+            r#"SELECT * FROM "sqlz" WHERE "name" LIKE 'O''Neil\%' ORDER BY "c1" DESC, "c2" LIMIT -1 OFFSET 5"#
+        );
+    }
+
+    #[test]
+    fn select_with_params() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": ["c1"],
+            "filter": [
+                {"column":"c1","equation":{"In":[1,2]}},
+                "AND",
+                {"column":"c2","equation":{"Between":[1.5,"x"]}},
+                "OR",
+                {"column":"c3","equation":{"Like":"'; DROP TABLE sqlz; --"}}
+            ],
+            "order": null,
+            "limit": 10,
+            "offset": null
+        }"#;
+        let select: Select = serde_json::from_str(json).unwrap();
+        let params = vec![
+            DataEnum::Integer(1),
+            DataEnum::Integer(2),
+            DataEnum::Float(1.5),
+            DataEnum::from("x"),
+            DataEnum::from("'; DROP TABLE sqlz; --"),
+        ];
+
+        let (sql, p) = SqlBuilder::new(Dialect::Postgres)
+            .select_with_params(&select)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT "c1" FROM "sqlz" WHERE "c1" IN ($1, $2) AND "c2" BETWEEN $3 AND $4 OR "c3" LIKE $5 LIMIT 10"#
+        );
+        assert_eq!(p, params);
+
+        let (sql, p) = SqlBuilder::new(Dialect::MySql)
+            .select_with_params(&select)
+            .unwrap();
+        assert_eq!(
+            sql,
+            "SELECT `c1` FROM `sqlz` WHERE `c1` IN (?, ?) AND `c2` BETWEEN ? AND ? OR `c3` LIKE ? LIMIT 10"
+        );
+        assert_eq!(p, params);
+
+        let (sql, p) = SqlBuilder::new(Dialect::Sqlite)
+            .select_with_params(&select)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT "c1" FROM "sqlz" WHERE "c1" IN (?, ?) AND "c2" BETWEEN ? AND ? OR "c3" LIKE ? LIMIT 10"#
+        );
+        assert_eq!(p, params);
+    }
+
+    #[test]
+    fn select_join() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "alias": "a",
+            "columns": ["a.c1", ["b.c2", "c2_t"], "c.*"],
+            "join": [
+                {
+                    "join_type": "Left",
+                    "table": "sqlz_b",
+                    "alias": "b",
+                    "on": [
+                        {"column":"a.id","equation":{"Column":["Equal","b.id"]}},
+                        "AND",
+                        {"column":"b.c3","equation":{"Greater":0}}
+                    ]
+                },
+                {"join_type": "Cross", "table": "sqlz_c", "alias": "c", "on": null}
+            ],
+            "filter": [{"column":"a.c1","equation":{"Column":["Less","b.c2"]}}],
+            "order": [{"name":"b.c2","order":"Asc"}],
+            "limit": null,
+            "offset": null
+        }"#;
+
+        assert_eq!(
+            render(Dialect::Postgres, json),
+            r#"SELECT "a"."c1", "b"."c2" AS "c2_t", "c".* FROM "sqlz" AS "a" LEFT JOIN "sqlz_b" AS "b" ON "a"."id" = "b"."id" AND "b"."c3" > 0 CROSS JOIN "sqlz_c" AS "c" WHERE "a"."c1" < "b"."c2" ORDER BY "b"."c2" ASC"#
+        );
+        assert_eq!(
+            render(Dialect::MySql, json),
+            "SELECT `a`.`c1`, `b`.`c2` AS `c2_t`, `c`.* FROM `sqlz` AS `a` LEFT JOIN `sqlz_b` AS `b` ON `a`.`id` = `b`.`id` AND `b`.`c3` > 0 CROSS JOIN `sqlz_c` AS `c` WHERE `a`.`c1` < `b`.`c2` ORDER BY `b`.`c2` ASC"
+        );
+    }
+
+    #[test]
+    fn select_join_invalid() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": [],
+            "join": [{"join_type": "Full", "table": "sqlz_b", "alias": null, "on": null}],
+            "filter": null,
+            "order": null,
+            "limit": null,
+            "offset": null
+        }"#;
+        let select: Select = serde_json::from_str(json).unwrap();
+
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Postgres).select(&select),
+            Err(SqlzError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            SqlBuilder::new(Dialect::MySql).select(&select),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn select_group_by() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": [
+                "c1",
+                {"function": "Count", "column": "*", "alias": "cnt"},
+                {"function": "CountDistinct", "column": "c2", "alias": null},
+                {"function": "Sum", "column": "c3", "alias": "total"}
+            ],
+            "filter": [{"column":"c4","equation":{"Equal":true}}],
+            "group_by": ["c1"],
+            "having": [
+                {"column":"cnt","equation":{"Greater":1}},
+                "AND",
+                {"column":"total","equation":{"LessEqual":100.5}},
+                "AND",
+                {"column":"cnt","equation":{"Column":["Less","total"]}}
+            ],
+            "order": [{"name":"total","order":"Desc"}],
+            "limit": null,
+            "offset": null
+        }"#;
+
+        assert_eq!(
+            render(Dialect::Postgres, json),
+            r#"SELECT "c1", COUNT(*) AS "cnt", COUNT(DISTINCT "c2"), SUM("c3") AS "total" FROM "sqlz" WHERE "c4" = TRUE GROUP BY "c1" HAVING COUNT(*) > 1 AND SUM("c3") <= 100.5 AND COUNT(*) < SUM("c3") ORDER BY "total" DESC"#
+        );
+        assert_eq!(
+            render(Dialect::Sqlite, json),
+            r#"SELECT "c1", COUNT(*) AS "cnt", COUNT(DISTINCT "c2"), SUM("c3") AS "total" FROM "sqlz" WHERE "c4" = 1 GROUP BY "c1" HAVING COUNT(*) > 1 AND SUM("c3") <= 100.5 AND COUNT(*) < SUM("c3") ORDER BY "total" DESC"#
+        );
+    }
+
+    #[test]
+    fn select_null_not_regex() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": [],
+            "filter": [
+                {"column":"c1","equation":{"Equal":null}},
+                "AND",
+                {"column":"c2","equation":"IsNotNull"},
+                "AND",
+                {"not": [
+                    {"column":"c3","equation":{"NotIn":[1,2]}},
+                    "OR",
+                    {"column":"c4","equation":{"NotBetween":[1,2]}}
+                ]},
+                "AND",
+                {"column":"c5","equation":{"ILike":"%abc%"}},
+                "AND",
+                {"column":"c6","equation":{"NotRegex":"^a.*"}}
+            ],
+            "order": null,
+            "limit": null,
+            "offset": null
+        }"#;
+
+        assert_eq!(
+            render(Dialect::Postgres, json),
+            r#"SELECT * FROM "sqlz" WHERE "c1" IS NULL AND "c2" IS NOT NULL AND NOT ("c3" NOT IN (1, 2) OR "c4" NOT BETWEEN 1 AND 2) AND "c5" ILIKE '%abc%' AND "c6" !~ '^a.*'"#
+        );
+        assert_eq!(
+            render(Dialect::MySql, json),
+            "SELECT * FROM `sqlz` WHERE `c1` IS NULL AND `c2` IS NOT NULL AND NOT (`c3` NOT IN (1, 2) OR `c4` NOT BETWEEN 1 AND 2) AND LOWER(`c5`) LIKE LOWER('%abc%') AND `c6` NOT REGEXP '^a.*'"
+        );
+
+        let select: Select = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Sqlite).select(&select),
+            Err(SqlzError::Unsupported(_))
+        ));
+
+        let (sql, params) = SqlBuilder::new(Dialect::Postgres)
+            .select_with_params(&select)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT * FROM "sqlz" WHERE "c1" IS NULL AND "c2" IS NOT NULL AND NOT ("c3" NOT IN ($1, $2) OR "c4" NOT BETWEEN $3 AND $4) AND "c5" ILIKE $5 AND "c6" !~ $6"#
+        );
+        assert_eq!(params.len(), 6);
+    }
+
+    #[test]
+    fn select_invalid_filter() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "columns": [],
+            "join": [{"join_type":"Inner","table":"t2","alias":null,"on":["AND"]}],
+            "filter": [
+                {"column":"c1","equation":{"Equal":1}},
+                {"column":"c2","equation":{"Equal":2}}
+            ],
+            "order": null,
+            "limit": null,
+            "offset": null
+        }"#;
+        let select: Select = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).select(&select),
+            Err(SqlzError::InvalidFilter(vec![
+                FilterError {
+                    path: "/join/0/on/0".to_owned(),
+                    kind: FilterErrorKind::LeadingConjunction,
+                },
+                FilterError {
+                    path: "/filter/1".to_owned(),
+                    kind: FilterErrorKind::MissingConjunction,
+                },
+            ]))
+        );
+    }
+
+    #[test]
+    fn select_subquery() {
+        let json = r#"
+        {
+            "with": [
+                {
+                    "name": "recent",
+                    "columns": ["id", "amount"],
+                    "select": {
+                        "table": "orders",
+                        "columns": ["id", "amount"],
+                        "filter": [{"column":"created","equation":{"Greater":"2021-01-01"}}],
+                        "order": null, "limit": null, "offset": null
+                    }
+                }
+            ],
+            "table": {
+                "select": {
+                    "table": "recent",
+                    "columns": ["id", "amount"],
+                    "filter": null, "order": null, "limit": null, "offset": null
+                }
+            },
+            "alias": "r",
+            "columns": ["r.id"],
+            "filter": [
+                {"column":"r.id","equation":{"InSelect":{
+                    "table": "refunds",
+                    "columns": ["order_id"],
+                    "filter": [{"column":"status","equation":{"Equal":"done"}}],
+                    "order": null, "limit": null, "offset": null
+                }}},
+                "OR",
+                {"not":[{"exists":{
+                    "table": "items",
+                    "columns": [],
+                    "filter": [{"column":"items.order_id","equation":{"Column":["Equal","r.id"]}}],
+                    "order": null, "limit": null, "offset": null
+                }}]}
+            ],
+            "order": [{"name":"id","order":null}],
+            "limit": 10,
+            "offset": null,
+            "set_operations": [
+                {"UnionAll": {
+                    "table": "archive",
+                    "columns": ["id"],
+                    "filter": [{"column":"amount","equation":{"Greater":0}}],
+                    "order": null, "limit": null, "offset": null
+                }}
+            ]
+        }"#;
+        let select: Select = serde_json::from_str(json).unwrap();
+
+        let (sql, params) = SqlBuilder::new(Dialect::Postgres)
+            .select_with_params(&select)
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"WITH "recent" ("id", "amount") AS (SELECT "id", "amount" FROM "orders" WHERE "created" > $1) SELECT "r"."id" FROM (SELECT "id", "amount" FROM "recent") AS "r" WHERE "r"."id" IN (SELECT "order_id" FROM "refunds" WHERE "status" = $2) OR NOT (EXISTS (SELECT * FROM "items" WHERE "items"."order_id" = "r"."id")) UNION ALL SELECT "id" FROM "archive" WHERE "amount" > $3 ORDER BY "id" LIMIT 10"#
+        );
+        assert_eq!(
+            params,
+            vec![
+                DataEnum::from("2021-01-01"),
+                DataEnum::from("done"),
+                DataEnum::Integer(0)
+            ]
+        );
+
+        let mut invalid = select.clone();
+        invalid.alias = None;
+        assert!(matches!(
+            SqlBuilder::new(Dialect::Sqlite).select(&invalid),
+            Err(SqlzError::InvalidValue(_))
+        ));
+
+        let mut invalid = select;
+        if let Some(ops) = &mut invalid.set_operations {
+            if let SetOperation::UnionAll(s) = &mut ops[0] {
+                s.limit = Some(1);
+                s.filter = Some(vec![Expression::Conjunction(Conjunction::AND)]);
+            }
+        }
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite).select(&invalid),
+            Err(SqlzError::InvalidFilter(vec![FilterError {
+                path: "/set_operations/0/UnionAll/filter/0".to_owned(),
+                kind: FilterErrorKind::LeadingConjunction,
+            }]))
+        );
+    }
+
+    #[test]
+    fn select_typed_values() {
+        let json = r#"
+        {
+            "table": "t",
+            "columns": ["id"],
+            "filter": [
+                {"column":"day","equation":{"Equal":{"Date":"2021-06-01"}}},
+                "AND",
+                {"column":"at","equation":{"Less":{"DateTime":"2021-06-01 08:30:00"}}},
+                "AND",
+                {"column":"price","equation":{"Greater":{"Decimal":"12.50"}}},
+                "AND",
+                {"column":"hash","equation":{"Equal":{"Bytes":"AQL/"}}},
+                "AND",
+                {"column":"uid","equation":{"Equal":{"Uuid":"67e55044-10b1-426f-9247-bb680e5fe0c8"}}}
+            ],
+            "order": null,
+            "limit": null,
+            "offset": null
+        }"#;
+
+        assert_eq!(
+            render(Dialect::Postgres, json),
+            "SELECT \"id\" FROM \"t\" WHERE \"day\" = DATE '2021-06-01' AND \"at\" < TIMESTAMP '2021-06-01 08:30:00' AND \"price\" > 12.50 AND \"hash\" = '\\x0102ff' AND \"uid\" = '67e55044-10b1-426f-9247-bb680e5fe0c8'"
+        );
+        assert_eq!(
+            render(Dialect::Sqlite, json),
+            "SELECT \"id\" FROM \"t\" WHERE \"day\" = '2021-06-01' AND \"at\" < '2021-06-01 08:30:00' AND \"price\" > 12.50 AND \"hash\" = X'0102ff' AND \"uid\" = '67e55044-10b1-426f-9247-bb680e5fe0c8'"
+        );
+        assert!(render(Dialect::MySql, json).contains("`day` = DATE '2021-06-01'"));
+    }
+}
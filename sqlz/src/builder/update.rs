@@ -0,0 +1,81 @@
+use super::writer::SqlWriter;
+use crate::{SqlzResult, Update, UpdateValue};
+
+impl SqlWriter {
+    pub(crate) fn write_update(&mut self, update: &Update) -> SqlzResult<()> {
+        update.check()?;
+
+        self.push("UPDATE ");
+        self.push_ident(&update.table);
+        self.push(" SET ");
+        let set = update.set.iter().collect::<Vec<_>>();
+        self.push_list(&set, ", ", |w, (column, value)| {
+            w.push_ident(column);
+            w.push(" = ");
+            match value {
+                UpdateValue::Value(v) => w.push_value(v),
+                UpdateValue::Column(c) => {
+                    w.push_ident(&c.column);
+                    Ok(())
+                }
+            }
+        })?;
+
+        self.write_filter(&update.filter)
+    }
+}
+
+#[cfg(test)]
+mod tests_update_builder {
+    use crate::{DataEnum, Dialect, SqlBuilder, SqlzError, Update};
+
+    #[test]
+    fn update() {
+        let json = r#"
+        {
+            "table": "sqlz",
+            "set": {"c1": "T1", "c2": {"column": "c3"}},
+            "filter": [{"column":"c4","equation":{"In":[1,2]}}]
+        }"#;
+        let update: Update = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).update(&update).unwrap(),
+            r#"UPDATE "sqlz" SET "c1" = 'T1', "c2" = "c3" WHERE "c4" IN (1, 2)"#
+        );
+
+        let (sql, params) = SqlBuilder::new(Dialect::MySql)
+            .update_with_params(&update)
+            .unwrap();
+        assert_eq!(
+            sql,
+            "UPDATE `sqlz` SET `c1` = ?, `c2` = `c3` WHERE `c4` IN (?, ?)"
+        );
+        assert_eq!(
+            params,
+            vec![
+                DataEnum::from("T1"),
+                DataEnum::Integer(1),
+                DataEnum::Integer(2)
+            ]
+        );
+    }
+
+    #[test]
+    fn update_unfiltered() {
+        let json = r#"{"table": "sqlz", "set": {"c1": 1}, "filter": null}"#;
+        let mut update: Update = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite).update(&update),
+            Err(SqlzError::Unfiltered("sqlz".to_owned()))
+        );
+
+        update.allow_unfiltered = Some(true);
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Sqlite).update(&update).unwrap(),
+            r#"UPDATE "sqlz" SET "c1" = 1"#
+        );
+    }
+}
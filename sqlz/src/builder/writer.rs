@@ -0,0 +1,124 @@
+use super::Dialect;
+use crate::{Aggregate, DataEnum, SqlzError, SqlzResult};
+
+/// accumulates Sql text for a specific dialect. When `params` is set, values
+/// are collected as bind values and replaced by placeholders in the text.
+/// `aggregates` are the aliased aggregates in scope while writing `HAVING`.
+/// `statements` holds finished statements when more than one is written.
+pub(crate) struct SqlWriter {
+    pub(crate) dialect: Dialect,
+    sql: String,
+    statements: Vec<String>,
+    params: Option<Vec<DataEnum>>,
+    pub(crate) aggregates: Vec<Aggregate>,
+}
+
+impl SqlWriter {
+    pub(crate) fn new(dialect: Dialect) -> Self {
+        SqlWriter {
+            dialect,
+            sql: String::new(),
+            statements: vec![],
+            params: None,
+            aggregates: vec![],
+        }
+    }
+
+    pub(crate) fn with_params(dialect: Dialect) -> Self {
+        SqlWriter {
+            dialect,
+            sql: String::new(),
+            statements: vec![],
+            params: Some(vec![]),
+            aggregates: vec![],
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.sql
+    }
+
+    pub(crate) fn finish_with_params(self) -> (String, Vec<DataEnum>) {
+        (self.sql, self.params.unwrap_or_default())
+    }
+
+    /// end the current statement and start a new one
+    pub(crate) fn end_statement(&mut self) {
+        let sql = std::mem::take(&mut self.sql);
+        self.statements.push(sql);
+    }
+
+    pub(crate) fn finish_statements(mut self) -> Vec<String> {
+        if !self.sql.is_empty() {
+            self.end_statement();
+        }
+        self.statements
+    }
+
+    pub(crate) fn push(&mut self, s: &str) {
+        self.sql.push_str(s);
+    }
+
+    pub(crate) fn push_ident(&mut self, ident: &str) {
+        let quoted = self.dialect.quote_ident(ident);
+        self.sql.push_str(&quoted);
+    }
+
+    /// push items separated by `sep`, each item written by `f`
+    pub(crate) fn push_list<T, F>(&mut self, items: &[T], sep: &str, mut f: F) -> SqlzResult<()>
+    where
+        F: FnMut(&mut Self, &T) -> SqlzResult<()>,
+    {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.push(sep);
+            }
+            f(self, item)?;
+        }
+        Ok(())
+    }
+
+    /// push a value, either as a placeholder or as an inline literal
+    pub(crate) fn push_value(&mut self, value: &DataEnum) -> SqlzResult<()> {
+        if let Some(params) = &mut self.params {
+            params.push(value.clone());
+            let placeholder = self.dialect.placeholder(params.len());
+            self.sql.push_str(&placeholder);
+            return Ok(());
+        }
+
+        let literal = match value {
+            DataEnum::Integer(v) => v.to_string(),
+            DataEnum::Float(v) => {
+                if !v.is_finite() {
+                    return Err(SqlzError::InvalidValue(format!("non-finite float {}", v)));
+                }
+                format!("{:?}", v)
+            }
+            DataEnum::String(v) => self.dialect.quote_string(v),
+            DataEnum::Bool(v) => self.dialect.bool_literal(*v).to_owned(),
+            DataEnum::Null => "NULL".to_owned(),
+            DataEnum::Date(_) | DataEnum::DateTime(_) | DataEnum::Time(_) => {
+                let text = self.dialect.quote_string(&value.typed_text().unwrap());
+                match (self.dialect, value) {
+                    (Dialect::Sqlite, _) => text,
+                    (_, DataEnum::Date(_)) => format!("DATE {}", text),
+                    (_, DataEnum::DateTime(_)) => format!("TIMESTAMP {}", text),
+                    _ => format!("TIME {}", text),
+                }
+            }
+            DataEnum::Decimal(_) => value.typed_text().unwrap(),
+            DataEnum::Uuid(_) => self.dialect.quote_string(&value.typed_text().unwrap()),
+            DataEnum::Json(v) => self.dialect.quote_string(&v.to_string()),
+            DataEnum::Bytes(v) => {
+                let hex = v.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+                match self.dialect {
+                    Dialect::Postgres => format!("'\\x{}'", hex),
+                    Dialect::MySql | Dialect::Sqlite => format!("X'{}'", hex),
+                }
+            }
+        };
+        self.sql.push_str(&literal);
+        Ok(())
+    }
+}
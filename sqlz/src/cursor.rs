@@ -0,0 +1,288 @@
+//! Keyset pagination of a select.
+//!
+//! A cursor encodes the `order` column values of the last row of a page. The
+//! next page is selected by comparing the `order` columns with them as a
+//! tuple, which is expanded into `(a > x) OR (a = x AND b > y) ...` so that
+//! columns can be ordered in different directions. The `order` columns should
+//! not be null, and together should be unique, e.g. end with the primary key.
+
+use serde_json::Value;
+
+use crate::{
+    ColumnAlias, Condition, Conjunction, DataEnum, Equation, Expression, OrderType, Select,
+    SelectPage, SelectVecResult, SqlzError, SqlzResult,
+};
+
+/// values of the `order` columns of a row, encoded as base64 of a JSON array
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor(pub Vec<DataEnum>);
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(&self.0).unwrap_or_default();
+        base64::encode_config(json, base64::URL_SAFE_NO_PAD)
+    }
+
+    pub fn decode(token: &str) -> SqlzResult<Self> {
+        let invalid = || SqlzError::InvalidValue(format!("cursor {}", token));
+        let json = base64::decode_config(token, base64::URL_SAFE_NO_PAD).map_err(|_| invalid())?;
+        let values = serde_json::from_slice(&json).map_err(|_| invalid())?;
+        Ok(Cursor(values))
+    }
+}
+
+impl Select {
+    /// `filter` combined with the keyset condition of `cursor`, which is used
+    /// in place of `filter` when the select is built or evaluated
+    pub fn keyset_filter(&self) -> SqlzResult<Option<Vec<Expression>>> {
+        let token = match &self.cursor {
+            Some(t) => t,
+            None => return Ok(self.filter.clone()),
+        };
+        let order = match &self.order {
+            Some(o) if !o.is_empty() => o,
+            _ => return Err(SqlzError::InvalidValue("cursor without order".to_owned())),
+        };
+        if self.offset.is_some() {
+            return Err(SqlzError::InvalidValue("cursor with offset".to_owned()));
+        }
+        if self.set_operations.is_some() {
+            return Err(SqlzError::InvalidValue(
+                "cursor with set operations".to_owned(),
+            ));
+        }
+        let Cursor(values) = Cursor::decode(token)?;
+        if values.len() != order.len() {
+            return Err(SqlzError::InvalidValue(format!(
+                "cursor of {} values for {} order columns",
+                values.len(),
+                order.len()
+            )));
+        }
+        if values.contains(&DataEnum::Null) {
+            return Err(SqlzError::Unsupported("null in cursor".to_owned()));
+        }
+
+        let columns = order
+            .iter()
+            .map(|o| self.source_column(&o.name))
+            .collect::<SqlzResult<Vec<_>>>()?;
+
+        let mut keyset = vec![];
+        for (i, o) in order.iter().enumerate() {
+            let mut and = vec![];
+            for (prev, v) in columns.iter().zip(values.iter()).take(i) {
+                and.push(condition(prev, Equation::Equal(v.clone())));
+                and.push(Expression::Conjunction(Conjunction::AND));
+            }
+            let v = values[i].clone();
+            and.push(condition(
+                columns[i],
+                match o.order {
+                    Some(OrderType::Desc) => Equation::Less(v),
+                    _ => Equation::Greater(v),
+                },
+            ));
+            if i > 0 {
+                keyset.push(Expression::Conjunction(Conjunction::OR));
+            }
+            keyset.push(Expression::Nest(and));
+        }
+
+        Ok(Some(match &self.filter {
+            Some(f) if !f.is_empty() => vec![
+                Expression::Nest(f.clone()),
+                Expression::Conjunction(Conjunction::AND),
+                Expression::Nest(keyset),
+            ],
+            _ => keyset,
+        }))
+    }
+
+    /// cursor of the page after `rows`, which are the result of this select.
+    /// It is none if the page is not full, or without `limit`. `order` columns
+    /// are looked up in rows by their output names, so they must be selected.
+    pub fn next_cursor(&self, rows: &SelectVecResult) -> SqlzResult<Option<String>> {
+        let limit = match self.limit {
+            Some(l) if l > 0 && rows.0.len() as u64 >= l => l,
+            _ => return Ok(None),
+        };
+        let last = match rows.0.get(limit as usize - 1) {
+            Some(r) => &r.0,
+            None => return Ok(None),
+        };
+        let mut values = vec![];
+        for o in self.order.iter().flatten() {
+            let value = last
+                .get(self.output_name(&o.name))
+                .or_else(|| last.get(&o.name))
+                .ok_or_else(|| {
+                    SqlzError::InvalidValue(format!("order column {} is not selected", o.name))
+                })?;
+            values.push(data(value)?);
+        }
+        if values.is_empty() {
+            return Err(SqlzError::InvalidValue("cursor without order".to_owned()));
+        }
+        Ok(Some(Cursor(values).encode()))
+    }
+
+    /// rows with the cursor of the next page
+    pub fn page(&self, rows: SelectVecResult) -> SqlzResult<SelectPage> {
+        let next_cursor = self.next_cursor(&rows)?;
+        Ok(SelectPage { rows, next_cursor })
+    }
+
+    /// evaluate a page in memory, see `evaluate`
+    pub fn evaluate_page(&self, rows: &[Value]) -> SqlzResult<SelectPage> {
+        self.page(self.evaluate(rows)?)
+    }
+
+    /// column of an `order` name in `WHERE`, where output aliases are not in
+    /// scope: an alias is taken as its source column, and aggregate, computed
+    /// or window aliases are rejected
+    pub(crate) fn source_column<'a>(&'a self, name: &'a str) -> SqlzResult<&'a str> {
+        for c in self.columns.iter() {
+            let alias = match c {
+                ColumnAlias::Simple(_) => None,
+                ColumnAlias::Alias((s, a)) if a == name => return Ok(s),
+                ColumnAlias::Alias(_) => None,
+                ColumnAlias::Aggregate(a) => a.alias.as_ref(),
+                ColumnAlias::Computed(c) => c.alias.as_ref(),
+                ColumnAlias::Window(w) => w.alias.as_ref(),
+            };
+            if matches!(alias, Some(a) if a == name) {
+                return Err(SqlzError::InvalidValue(format!(
+                    "cursor order {} is not a source column",
+                    name
+                )));
+            }
+        }
+        Ok(name)
+    }
+
+    /// name of a column in result rows
+    fn output_name<'a>(&'a self, column: &'a str) -> &'a str {
+        self.columns
+            .iter()
+            .find_map(|c| match c {
+                ColumnAlias::Alias((s, a)) if s == column => Some(a.as_str()),
+                _ => None,
+            })
+            .unwrap_or_else(|| column.rsplit('.').next().unwrap_or(column))
+    }
+}
+
+fn condition(column: &str, equation: Equation) -> Expression {
+    Expression::Simple(Condition {
+        column: column.to_owned(),
+        equation,
+    })
+}
+
+fn data(value: &Value) -> SqlzResult<DataEnum> {
+    match value {
+        Value::Array(_) | Value::Object(_) => {
+            Err(SqlzError::InvalidValue(format!("cursor value {}", value)))
+        }
+        v => serde_json::from_value(v.clone())
+            .map_err(|_| SqlzError::InvalidValue(format!("cursor value {}", v))),
+    }
+}
+
+#[cfg(test)]
+mod tests_cursor {
+    use super::*;
+    use crate::{Dialect, SqlBuilder};
+    use serde_json::json;
+
+    fn select(cursor: Option<String>) -> Select {
+        let json = r#"
+        {
+            "table": "t",
+            "columns": ["id", ["score", "s"]],
+            "filter": [{"column": "score", "equation": "IsNotNull"}],
+            "order": [{"name": "score", "order": "Desc"}, {"name": "id", "order": null}],
+            "limit": 2,
+            "offset": null
+        }"#;
+        Select {
+            cursor,
+            ..serde_json::from_str(json).unwrap()
+        }
+    }
+
+    #[test]
+    fn cursor_sql() {
+        let cursor = Cursor(vec![DataEnum::Integer(5), DataEnum::Integer(7)]).encode();
+        assert_eq!(
+            Cursor::decode(&cursor).unwrap(),
+            Cursor(vec![DataEnum::Integer(5), DataEnum::Integer(7)])
+        );
+
+        let sql = SqlBuilder::new(Dialect::Postgres)
+            .select(&select(Some(cursor)))
+            .unwrap();
+        assert_eq!(
+            sql,
+            r#"SELECT "id", "score" AS "s" FROM "t" WHERE ("score" IS NOT NULL) AND (("score" < 5) OR ("score" = 5 AND "id" > 7)) ORDER BY "score" DESC, "id" LIMIT 2"#
+        );
+
+        assert!(select(Some("?".to_owned())).keyset_filter().is_err());
+        let offset = Select {
+            offset: Some(2),
+            ..select(Some(Cursor(vec![1.into(), 2.into()]).encode()))
+        };
+        assert!(offset.keyset_filter().is_err());
+    }
+
+    #[test]
+    fn cursor_aliases() {
+        let cursor = Cursor(vec![DataEnum::Integer(5)]).encode();
+        let aliased: Select = serde_json::from_str(&format!(
+            r#"{{"table": "t", "columns": [["score", "s"]], "filter": null, "order": [{{"name": "s", "order": null}}], "limit": 2, "offset": null, "cursor": "{}"}}"#,
+            cursor
+        ))
+        .unwrap();
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).select(&aliased).unwrap(),
+            r#"SELECT "score" AS "s" FROM "t" WHERE ("score" > 5) ORDER BY "s" LIMIT 2"#
+        );
+
+        let aggregated: Select = serde_json::from_str(&format!(
+            r#"{{"table": "t", "columns": [{{"function": "Count", "column": "*", "alias": "cnt"}}], "filter": null, "order": [{{"name": "cnt", "order": null}}], "limit": 2, "offset": null, "cursor": "{}"}}"#,
+            cursor
+        ))
+        .unwrap();
+        assert!(matches!(
+            aggregated.keyset_filter(),
+            Err(SqlzError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn cursor_pages() {
+        let rows = (1..=5)
+            .map(|i| json!({"id": i, "score": i % 3}))
+            .collect::<Vec<_>>();
+
+        let mut ids = vec![];
+        let mut cursor = None;
+        loop {
+            let page = select(cursor).evaluate_page(&rows).unwrap();
+            ids.push(
+                page.rows
+                    .0
+                    .iter()
+                    .map(|r| r.0["id"].as_i64().unwrap())
+                    .collect::<Vec<_>>(),
+            );
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        assert_eq!(ids, vec![vec![2, 5], vec![1, 4], vec![3]]);
+    }
+}
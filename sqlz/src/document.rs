@@ -0,0 +1,423 @@
+//! Generates ER diagrams and data dictionaries from schemas.
+//!
+//! - `Schema::to_mermaid`: Mermaid `erDiagram`
+//! - `Schema::to_dot`: Graphviz DOT graph, a table per node
+//! - `Schema::to_markdown`: Markdown data dictionary, with table and column
+//!   comments as descriptions
+
+use crate::{
+    Column, ColumnDefault, ColumnKey, ColumnType, DataEnum, Index, Order, OrderType, Schema, Table,
+};
+
+impl Schema {
+    /// Mermaid `erDiagram`. Names are reduced to the characters Mermaid
+    /// accepts, and a foreign key is a relationship from the referred table.
+    pub fn to_mermaid(&self) -> String {
+        let mut res = String::from("erDiagram\n");
+        for t in self.tables.iter() {
+            res.push_str(&format!("    {} {{\n", mermaid_word(&t.name)));
+            for c in t.columns.iter() {
+                res.push_str(&format!(
+                    "        {} {}",
+                    mermaid_word(&type_label(&c.col_type).replace(", ", "-")),
+                    mermaid_word(&c.name)
+                ));
+                let keys = column_keys(t, c);
+                if !keys.is_empty() {
+                    res.push_str(&format!(" {}", keys.join(", ")));
+                }
+                if let Some(comment) = &c.comment {
+                    res.push_str(&format!(" \"{}\"", one_line(comment).replace('"', "'")));
+                }
+                res.push('\n');
+            }
+            res.push_str("    }\n");
+        }
+        for t in self.tables.iter() {
+            for fk in t.foreign_keys.iter() {
+                let columns = fk
+                    .from
+                    .columns
+                    .iter()
+                    .filter_map(|name| t.columns.iter().find(|c| &c.name == name))
+                    .collect::<Vec<_>>();
+                let required = columns.iter().all(|c| c.null == Some(false));
+                let unique = matches!(
+                    columns.as_slice(),
+                    [c] if matches!(c.key, Some(ColumnKey::Primary | ColumnKey::Unique))
+                );
+                res.push_str(&format!(
+                    "    {} {}--{} {} : \"{}\"\n",
+                    mermaid_word(&fk.to.table),
+                    if required { "||" } else { "|o" },
+                    if unique { "o|" } else { "o{" },
+                    mermaid_word(&t.name),
+                    one_line(&fk.name).replace('"', "'")
+                ));
+            }
+        }
+        res
+    }
+
+    /// Graphviz DOT graph with HTML-like labels, a row per column and an edge
+    /// per foreign key between the first columns
+    pub fn to_dot(&self) -> String {
+        let mut res = format!("digraph {} {{\n", dot_string(&self.schema));
+        res.push_str("    rankdir=LR;\n    node [shape=plain];\n");
+        for t in self.tables.iter() {
+            let mut label = String::from(r#"<table border="0" cellborder="1" cellspacing="0">"#);
+            label.push_str(&format!(
+                r#"<tr><td colspan="3" bgcolor="lightgrey"><b>{}</b></td></tr>"#,
+                html(&t.name)
+            ));
+            if let Some(comment) = &t.comment {
+                label.push_str(&format!(
+                    r#"<tr><td colspan="3"><i>{}</i></td></tr>"#,
+                    html(comment)
+                ));
+            }
+            for c in t.columns.iter() {
+                let title = match &c.comment {
+                    Some(comment) => format!(r#" title="{}""#, html(comment)),
+                    None => String::new(),
+                };
+                label.push_str(&format!(
+                    r#"<tr><td port="{0}" align="left"{1}>{0}</td><td align="left">{2}</td><td>{3}</td></tr>"#,
+                    html(&c.name),
+                    title,
+                    html(&type_label(&c.col_type)),
+                    column_keys(t, c).join(", ")
+                ));
+            }
+            label.push_str("</table>");
+            res.push_str(&format!(
+                "    {} [label=<{}>];\n",
+                dot_string(&t.name),
+                label
+            ));
+        }
+        for t in self.tables.iter() {
+            for fk in t.foreign_keys.iter() {
+                let (from, to) = match (fk.from.columns.first(), fk.to.columns.first()) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => continue,
+                };
+                res.push_str(&format!(
+                    "    {}:{} -> {}:{} [label={}];\n",
+                    dot_string(&t.name),
+                    dot_string(from),
+                    dot_string(&fk.to.table),
+                    dot_string(to),
+                    dot_string(&fk.name)
+                ));
+            }
+        }
+        res.push_str("}\n");
+        res
+    }
+
+    /// Markdown data dictionary, a section per table with its columns,
+    /// indexes, composite unique keys and foreign keys
+    pub fn to_markdown(&self) -> String {
+        let mut res = format!("# {}\n", self.schema);
+        for t in self.tables.iter() {
+            res.push_str(&format!("\n## {}\n\n", t.name));
+            if let Some(comment) = &t.comment {
+                res.push_str(comment);
+                res.push_str("\n\n");
+            }
+            res.push_str("| Column | Type | Null | Key | Default | Comment |\n");
+            res.push_str("| --- | --- | --- | --- | --- | --- |\n");
+            for c in t.columns.iter() {
+                let default = match &c.default {
+                    Some(d) => default_label(d),
+                    None => String::new(),
+                };
+                let cells = [
+                    format!("`{}`", c.name),
+                    type_label(&c.col_type),
+                    if c.null == Some(false) { "NO" } else { "YES" }.to_owned(),
+                    column_keys(t, c).join(", "),
+                    default,
+                    c.comment.clone().unwrap_or_default(),
+                ];
+                let cells = cells.iter().map(|c| cell(c)).collect::<Vec<_>>();
+                res.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+
+            let indexes = table_indexes(self, t);
+            if !indexes.is_empty() {
+                res.push_str("\n### Indexes\n\n");
+                for i in indexes.iter() {
+                    let columns = i.columns.iter().map(order_label).collect::<Vec<_>>();
+                    res.push_str(&format!("- `{}` ({})\n", i.name, columns.join(", ")));
+                }
+            }
+            let unique_keys = t
+                .unique_keys
+                .iter()
+                .flatten()
+                .filter(|u| u.columns.len() > 1)
+                .collect::<Vec<_>>();
+            if !unique_keys.is_empty() {
+                res.push_str("\n### Unique keys\n\n");
+                for u in unique_keys {
+                    res.push_str(&format!("- `{}` ({})\n", u.name, code_list(&u.columns)));
+                }
+            }
+            if !t.foreign_keys.is_empty() {
+                res.push_str("\n### Foreign keys\n\n");
+                for fk in t.foreign_keys.iter() {
+                    res.push_str(&format!(
+                        "- `{}` ({}) → `{}` ({}), on delete {:?}, on update {:?}\n",
+                        fk.name,
+                        code_list(&fk.from.columns),
+                        fk.to.table,
+                        code_list(&fk.to.columns),
+                        fk.on_delete,
+                        fk.on_update
+                    ));
+                }
+            }
+        }
+        res
+    }
+}
+
+/// type with its size, e.g. `Decimal(10, 2)`
+fn type_label(t: &ColumnType) -> String {
+    match t {
+        ColumnType::Char(Some(n)) | ColumnType::VarChar(Some(n)) => {
+            format!("{}({})", t.name(), n)
+        }
+        ColumnType::Decimal(p, s) => format!("Decimal({}, {})", p, s),
+        ColumnType::Enum(v) => format!("Enum({})", v.join(", ")),
+        ColumnType::Array(t) => format!("Array({})", type_label(t)),
+        t => t.name().to_owned(),
+    }
+}
+
+/// `PK`, `UK` and `FK`, as Mermaid names them
+fn column_keys(table: &Table, column: &Column) -> Vec<&'static str> {
+    let mut keys = vec![];
+    if table.primary_key_columns().contains(&column.name.as_str()) {
+        keys.push("PK");
+    } else if column.key == Some(ColumnKey::Unique) {
+        keys.push("UK");
+    }
+    if table
+        .foreign_keys
+        .iter()
+        .any(|fk| fk.from.columns.contains(&column.name))
+    {
+        keys.push("FK");
+    }
+    keys
+}
+
+/// indexes of the schema on a table, and those of `Multiple` key columns
+/// named as in `CREATE TABLE`
+fn table_indexes(schema: &Schema, table: &Table) -> Vec<Index> {
+    let mut indexes = schema
+        .indexes
+        .iter()
+        .flatten()
+        .filter(|i| i.table == table.name)
+        .cloned()
+        .collect::<Vec<_>>();
+    for c in table
+        .columns
+        .iter()
+        .filter(|c| c.key == Some(ColumnKey::Multiple))
+    {
+        indexes.push(Index {
+            name: format!("{}_{}_index", table.name, c.name),
+            table: table.name.clone(),
+            columns: vec![Order {
+                name: c.name.clone(),
+                order: None,
+            }],
+        });
+    }
+    indexes
+}
+
+fn default_label(default: &ColumnDefault) -> String {
+    match default {
+        ColumnDefault::Value(DataEnum::String(s)) => format!("'{}'", s),
+        ColumnDefault::Value(DataEnum::Null) => "NULL".to_owned(),
+        ColumnDefault::Value(DataEnum::Json(v)) => format!("'{}'", v),
+        ColumnDefault::Value(DataEnum::Bytes(v)) => {
+            let hex = v.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+            format!("X'{}'", hex)
+        }
+        ColumnDefault::Value(v @ DataEnum::Decimal(_)) => v.typed_text().unwrap_or_default(),
+        ColumnDefault::Value(v) => match v.typed_text() {
+            Some(text) => format!("'{}'", text),
+            None => serde_json::to_string(v).unwrap_or_default(),
+        },
+        ColumnDefault::CurrentTimestamp => "CURRENT_TIMESTAMP".to_owned(),
+        ColumnDefault::CurrentDate => "CURRENT_DATE".to_owned(),
+        ColumnDefault::CurrentTime => "CURRENT_TIME".to_owned(),
+    }
+}
+
+fn order_label(o: &Order) -> String {
+    match o.order {
+        Some(OrderType::Desc) => format!("`{}` DESC", o.name),
+        _ => format!("`{}`", o.name),
+    }
+}
+
+fn code_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|n| format!("`{}`", n))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn one_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Markdown table cell
+fn cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// Mermaid entity, attribute and type names, other characters become `_`
+fn mermaid_word(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            c if c.is_ascii_alphanumeric() => c,
+            '-' | '(' | ')' | '[' | ']' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests_document {
+    use super::*;
+
+    fn schema() -> Schema {
+        let json = r#"
+        {
+            "schema": "shop",
+            "tables": [
+                {
+                    "name": "customers",
+                    "columns": [
+                        {"name": "id", "col_type": "Int", "null": false, "key": "Primary"},
+                        {"name": "email", "col_type": {"VarChar": 255}, "null": false, "key": "Unique", "comment": "login | contact"}
+                    ],
+                    "comment": "people who <buy>"
+                },
+                {
+                    "name": "orders",
+                    "columns": [
+                        {"name": "id", "col_type": "BigInt", "null": false, "key": "Primary"},
+                        {"name": "customer_id", "col_type": "Int", "null": true, "key": "Multiple"},
+                        {"name": "total", "col_type": {"Decimal": [10, 2]}, "null": false, "key": null, "default": {"Value": {"Decimal": "0.00"}}},
+                        {"name": "status", "col_type": "Text", "null": false, "key": null, "default": {"Value": "new"}, "comment": "order \"status\""}
+                    ],
+                    "foreign_keys": [
+                        {
+                            "name": "fk_customer",
+                            "from": {"table": "orders", "columns": ["customer_id"]},
+                            "to": {"table": "customers", "columns": ["id"]},
+                            "on_delete": "SetNull",
+                            "on_update": "NoAction"
+                        }
+                    ]
+                }
+            ],
+            "indexes": [
+                {"name": "idx_orders_status", "table": "orders", "columns": [{"name": "status", "order": null}, {"name": "total", "order": "Desc"}]}
+            ]
+        }"#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn mermaid() {
+        assert_eq!(
+            schema().to_mermaid(),
+            r#"erDiagram
+    customers {
+        Int id PK
+        VarChar(255) email UK "login | contact"
+    }
+    orders {
+        BigInt id PK
+        Int customer_id FK
+        Decimal(10-2) total
+        Text status "order 'status'"
+    }
+    customers |o--o{ orders : "fk_customer"
+"#
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let dot = schema().to_dot();
+
+        assert!(dot.starts_with("digraph \"shop\" {\n    rankdir=LR;\n"));
+        assert!(dot.contains(r#"<tr><td colspan="3"><i>people who &lt;buy&gt;</i></td></tr>"#));
+        assert!(dot.contains(
+            r#"<tr><td port="status" align="left" title="order &quot;status&quot;">status</td><td align="left">Text</td><td></td></tr>"#
+        ));
+        assert!(dot.contains(
+            "    \"orders\":\"customer_id\" -> \"customers\":\"id\" [label=\"fk_customer\"];\n"
+        ));
+    }
+
+    #[test]
+    fn markdown() {
+        assert_eq!(
+            schema().to_markdown(),
+            r#"# shop
+
+## customers
+
+people who <buy>
+
+| Column | Type | Null | Key | Default | Comment |
+| --- | --- | --- | --- | --- | --- |
+| `id` | Int | NO | PK |  |  |
+| `email` | VarChar(255) | NO | UK |  | login \| contact |
+
+## orders
+
+| Column | Type | Null | Key | Default | Comment |
+| --- | --- | --- | --- | --- | --- |
+| `id` | BigInt | NO | PK |  |  |
+| `customer_id` | Int | YES | FK |  |  |
+| `total` | Decimal(10, 2) | NO |  | 0.00 |  |
+| `status` | Text | NO |  | 'new' | order "status" |
+
+### Indexes
+
+- `idx_orders_status` (`status`, `total` DESC)
+- `orders_customer_id_index` (`customer_id`)
+
+### Foreign keys
+
+- `fk_customer` (`customer_id`) → `customers` (`id`), on delete SetNull, on update NoAction
+"#
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+use crate::filter::FilterError;
+use crate::parser::ParseError;
+use crate::policy::PolicyViolation;
+
+pub type SqlzResult<T> = Result<T, SqlzError>;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SqlzError {
+    #[error("common error {0}")]
+    CommonError(String),
+    #[error("invalid value {0}")]
+    InvalidValue(String),
+    #[error("unsupported {0}")]
+    Unsupported(String),
+    #[error("unfiltered statement on table {0} refused")]
+    Unfiltered(String),
+    #[error("invalid filter {0:?}")]
+    InvalidFilter(Vec<FilterError>),
+    #[error("parse error {0}")]
+    Parse(ParseError),
+    #[error("policy violation {0:?}")]
+    Policy(Vec<PolicyViolation>),
+}
@@ -0,0 +1,524 @@
+//! Evaluates a select against JSON rows in memory.
+
+use std::cmp::Ordering;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::{Map, Value};
+
+use crate::{
+    ColumnAlias, Comparison, Condition, DataEnum, Equation, FilterTree, OrderType, Select,
+    SelectResult, SelectVecResult, SqlzError, SqlzResult, TableSource,
+};
+
+impl Select {
+    /// evaluate the select against rows of JSON objects, as a database does.
+    ///
+    /// Comparisons follow Sql: anything compared with `null` or a value of
+    /// another type is unknown, which filters the row out, also under `NOT`.
+    /// `null` sorts after other values, as in Postgres. Qualified columns such
+    /// as `t1.c1` fall back to the unqualified key. Joins, grouping, aggregates,
+    /// computed columns, subqueries, set operations and regex are not supported.
+    pub fn evaluate(&self, rows: &[Value]) -> SqlzResult<SelectVecResult> {
+        if self.with.is_some() || matches!(self.table, TableSource::Derived(_)) {
+            return Err(SqlzError::Unsupported("subquery in memory".to_owned()));
+        }
+        if self.set_operations.is_some() {
+            return Err(SqlzError::Unsupported("set operation in memory".to_owned()));
+        }
+        if matches!(&self.join, Some(j) if !j.is_empty()) {
+            return Err(SqlzError::Unsupported("join in memory".to_owned()));
+        }
+        if matches!(&self.group_by, Some(g) if !g.is_empty())
+            || matches!(&self.having, Some(h) if !h.is_empty())
+        {
+            return Err(SqlzError::Unsupported("grouping in memory".to_owned()));
+        }
+        if self
+            .columns
+            .iter()
+            .any(|c| matches!(c, ColumnAlias::Aggregate(_)))
+        {
+            return Err(SqlzError::Unsupported("aggregate in memory".to_owned()));
+        }
+        if self
+            .columns
+            .iter()
+            .any(|c| matches!(c, ColumnAlias::Computed(_) | ColumnAlias::Window(_)))
+        {
+            return Err(SqlzError::Unsupported(
+                "computed column in memory".to_owned(),
+            ));
+        }
+
+        let tree = FilterTree::normalize(self.keyset_filter()?.as_deref().unwrap_or_default())?;
+
+        let mut selected = vec![];
+        for row in rows.iter() {
+            let row = row
+                .as_object()
+                .ok_or_else(|| SqlzError::InvalidValue(format!("row {} is not an object", row)))?;
+            let keep = match &tree {
+                Some(t) => eval_tree(t, row)? == Some(true),
+                None => true,
+            };
+            if keep {
+                selected.push(row);
+            }
+        }
+
+        for o in self.order.iter().flatten().rev() {
+            let desc = matches!(o.order, Some(OrderType::Desc));
+            selected.sort_by(|a, b| {
+                let ord = sort_cmp(lookup(a, &o.name), lookup(b, &o.name));
+                if desc {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            });
+        }
+
+        let offset = self.offset.unwrap_or_default() as usize;
+        let limit = self.limit.map_or(usize::MAX, |l| l as usize);
+
+        let res = selected
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|row| SelectResult(Value::Object(self.project(row))))
+            .collect();
+
+        Ok(SelectVecResult(res))
+    }
+
+    fn project(&self, row: &Map<String, Value>) -> Map<String, Value> {
+        if self.columns.is_empty() {
+            return row.clone();
+        }
+        let mut res = Map::new();
+        for c in self.columns.iter() {
+            let (column, key) = match c {
+                ColumnAlias::Simple(s) if s == "*" => {
+                    res.extend(row.clone());
+                    continue;
+                }
+                ColumnAlias::Simple(s) => (s, unqualified(s)),
+                ColumnAlias::Alias((s, a)) => (s, a.as_str()),
+                ColumnAlias::Aggregate(_) | ColumnAlias::Computed(_) | ColumnAlias::Window(_) => {
+                    unreachable!()
+                }
+            };
+            let value = lookup(row, column).cloned().unwrap_or(Value::Null);
+            res.insert(key.to_owned(), value);
+        }
+        res
+    }
+}
+
+fn unqualified(column: &str) -> &str {
+    column.rsplit('.').next().unwrap_or(column)
+}
+
+fn lookup<'a>(row: &'a Map<String, Value>, column: &str) -> Option<&'a Value> {
+    row.get(column).or_else(|| row.get(unqualified(column)))
+}
+
+/// `None` stands for unknown
+fn eval_tree(tree: &FilterTree, row: &Map<String, Value>) -> SqlzResult<Option<bool>> {
+    match tree {
+        FilterTree::And(v) => {
+            let mut res = Some(true);
+            for t in v {
+                match eval_tree(t, row)? {
+                    Some(false) => return Ok(Some(false)),
+                    None => res = None,
+                    Some(true) => {}
+                }
+            }
+            Ok(res)
+        }
+        FilterTree::Or(v) => {
+            let mut res = Some(false);
+            for t in v {
+                match eval_tree(t, row)? {
+                    Some(true) => return Ok(Some(true)),
+                    None => res = None,
+                    Some(false) => {}
+                }
+            }
+            Ok(res)
+        }
+        FilterTree::Not(t) => Ok(eval_tree(t, row)?.map(|b| !b)),
+        FilterTree::Condition(c) => eval_condition(c, row),
+        FilterTree::Exists(_) => Err(SqlzError::Unsupported("subquery in memory".to_owned())),
+    }
+}
+
+fn eval_condition(condition: &Condition, row: &Map<String, Value>) -> SqlzResult<Option<bool>> {
+    let value = lookup(row, &condition.column).unwrap_or(&Value::Null);
+    let cmp = |d: &DataEnum, comparison: Comparison| {
+        compare_data(value, d).map(|o| comparison_holds(&comparison, o))
+    };
+
+    let res = match &condition.equation {
+        Equation::Equal(DataEnum::Null) | Equation::IsNull => Some(value.is_null()),
+        Equation::NotEqual(DataEnum::Null) | Equation::IsNotNull => Some(!value.is_null()),
+        Equation::Equal(d) => cmp(d, Comparison::Equal),
+        Equation::NotEqual(d) => cmp(d, Comparison::NotEqual),
+        Equation::Greater(d) => cmp(d, Comparison::Greater),
+        Equation::GreaterEqual(d) => cmp(d, Comparison::GreaterEqual),
+        Equation::Less(d) => cmp(d, Comparison::Less),
+        Equation::LessEqual(d) => cmp(d, Comparison::LessEqual),
+        Equation::In(v) => eval_in(value, v),
+        Equation::NotIn(v) => eval_in(value, v).map(|b| !b),
+        Equation::Between((lo, hi)) => eval_between(value, lo, hi),
+        Equation::NotBetween((lo, hi)) => eval_between(value, lo, hi).map(|b| !b),
+        Equation::Like(p) => value.as_str().map(|s| like(s, p, false)),
+        Equation::NotLike(p) => value.as_str().map(|s| !like(s, p, false)),
+        Equation::ILike(p) => value.as_str().map(|s| like(s, p, true)),
+        Equation::NotILike(p) => value.as_str().map(|s| !like(s, p, true)),
+        Equation::Column((comparison, column)) => {
+            let other = lookup(row, column).unwrap_or(&Value::Null);
+            compare(value, other).map(|o| comparison_holds(comparison, o))
+        }
+        Equation::Regex(_) | Equation::NotRegex(_) => {
+            return Err(SqlzError::Unsupported("regex in memory".to_owned()))
+        }
+        Equation::InSelect(_) | Equation::NotInSelect(_) => {
+            return Err(SqlzError::Unsupported("subquery in memory".to_owned()))
+        }
+    };
+    Ok(res)
+}
+
+fn eval_in(value: &Value, list: &[DataEnum]) -> Option<bool> {
+    let mut res = Some(false);
+    for d in list {
+        match compare_data(value, d) {
+            Some(Ordering::Equal) => return Some(true),
+            None => res = None,
+            Some(_) => {}
+        }
+    }
+    res
+}
+
+fn eval_between(value: &Value, lo: &DataEnum, hi: &DataEnum) -> Option<bool> {
+    let ge = compare_data(value, lo).map(|o| o != Ordering::Less);
+    let le = compare_data(value, hi).map(|o| o != Ordering::Greater);
+    match (ge, le) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+/// dates and times compare with rows parsed as such, with either a space or
+/// `T` separating the date and time, and a row which does not parse is unknown
+fn compare_data(value: &Value, d: &DataEnum) -> Option<Ordering> {
+    let text = value.as_str();
+    match d {
+        DataEnum::Date(v) => Some(NaiveDate::parse_from_str(text?, "%Y-%m-%d").ok()?.cmp(v)),
+        DataEnum::DateTime(v) => {
+            let text = text?;
+            let row = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+                .ok()?;
+            Some(row.cmp(v))
+        }
+        DataEnum::Time(v) => Some(NaiveTime::parse_from_str(text?, "%H:%M:%S%.f").ok()?.cmp(v)),
+        d => compare(value, &to_value(d)),
+    }
+}
+
+/// other typed values compare with rows as their text, decimals as numbers and
+/// bytes as base64
+fn to_value(d: &DataEnum) -> Value {
+    match d {
+        DataEnum::Decimal(v) => v
+            .to_string()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map_or(Value::Null, Value::Number),
+        DataEnum::Bytes(v) => Value::String(base64::encode(v)),
+        DataEnum::Json(v) => v.clone(),
+        d => match d.typed_text() {
+            Some(text) => Value::String(text),
+            None => serde_json::to_value(d).unwrap_or(Value::Null),
+        },
+    }
+}
+
+fn comparison_holds(comparison: &Comparison, o: Ordering) -> bool {
+    match comparison {
+        Comparison::Equal => o == Ordering::Equal,
+        Comparison::NotEqual => o != Ordering::Equal,
+        Comparison::Greater => o == Ordering::Greater,
+        Comparison::GreaterEqual => o != Ordering::Less,
+        Comparison::Less => o == Ordering::Less,
+        Comparison::LessEqual => o != Ordering::Greater,
+    }
+}
+
+/// `None` if either is `null` or they are of different types
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => match (x.as_i64(), y.as_i64()) {
+            (Some(x), Some(y)) => Some(x.cmp(&y)),
+            _ => x.as_f64()?.partial_cmp(&y.as_f64()?),
+        },
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
+
+/// total order for sorting, values of different types are ordered by type and
+/// `null` comes last
+fn sort_cmp(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    fn rank(v: Option<&Value>) -> u8 {
+        match v {
+            Some(Value::Bool(_)) => 0,
+            Some(Value::Number(_)) => 1,
+            Some(Value::String(_)) => 2,
+            Some(Value::Array(_)) | Some(Value::Object(_)) => 3,
+            Some(Value::Null) | None => 4,
+        }
+    }
+    match (a, b) {
+        (Some(x), Some(y)) => compare(x, y),
+        _ => None,
+    }
+    .unwrap_or_else(|| rank(a).cmp(&rank(b)))
+}
+
+/// `%` matches any sequence, `_` matches a single character and `\` escapes
+fn like(s: &str, pattern: &str, case_insensitive: bool) -> bool {
+    let (s, pattern) = if case_insensitive {
+        (s.to_lowercase(), pattern.to_lowercase())
+    } else {
+        (s.to_owned(), pattern.to_owned())
+    };
+    let s = s.chars().collect::<Vec<_>>();
+    let p = like_tokens(&pattern);
+    like_chars(&s, &p)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum LikeToken {
+    Any,
+    One,
+    Char(char),
+}
+
+fn like_tokens(pattern: &str) -> Vec<LikeToken> {
+    let mut tokens = vec![];
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        tokens.push(match c {
+            '%' => LikeToken::Any,
+            '_' => LikeToken::One,
+            '\\' => LikeToken::Char(chars.next().unwrap_or('\\')),
+            c => LikeToken::Char(c),
+        });
+    }
+    tokens
+}
+
+/// iterative matching, a mismatch backtracks only to the last `%`
+fn like_chars(s: &[char], p: &[LikeToken]) -> bool {
+    let (mut i, mut j) = (0, 0);
+    let mut last_any = None;
+    while i < s.len() {
+        match p.get(j) {
+            Some(LikeToken::Any) => {
+                last_any = Some((j, i));
+                j += 1;
+            }
+            Some(LikeToken::One) => {
+                i += 1;
+                j += 1;
+            }
+            Some(LikeToken::Char(c)) if *c == s[i] => {
+                i += 1;
+                j += 1;
+            }
+            _ => match last_any {
+                Some((any, matched)) => {
+                    last_any = Some((any, matched + 1));
+                    i = matched + 1;
+                    j = any + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    p[j..].iter().all(|t| *t == LikeToken::Any)
+}
+
+#[cfg(test)]
+mod tests_evaluate {
+    use super::*;
+    use serde_json::json;
+
+    fn rows() -> Vec<Value> {
+        vec![
+            json!({"id": 1, "name": "Apple", "price": 2.5, "tag": "fruit"}),
+            json!({"id": 2, "name": "banana", "price": 1, "tag": null}),
+            json!({"id": 3, "name": "Cherry", "price": 10, "tag": "fruit"}),
+            json!({"id": 4, "name": "apricot", "price": 4}),
+            json!({"id": 5, "name": "50% off", "price": null, "tag": "sale"}),
+        ]
+    }
+
+    fn evaluate(json: &str) -> Vec<Value> {
+        let select: Select = serde_json::from_str(json).unwrap();
+        select
+            .evaluate(&rows())
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|r| r.0)
+            .collect()
+    }
+
+    #[test]
+    fn evaluation() {
+        let res = evaluate(
+            r#"
+            {
+                "table": "t",
+                "columns": ["t.id", ["name", "n"]],
+                "filter": [
+                    {"column":"price","equation":{"Between":[1,5]}},
+                    "OR",
+                    {"column":"tag","equation":{"Equal":"sale"}}
+                ],
+                "order": [{"name":"price","order":"Desc"}, {"name":"id","order":null}],
+                "limit": 3,
+                "offset": 1
+            }"#,
+        );
+
+        assert_eq!(
+            res,
+            vec![
+                json!({"id": 4, "n": "apricot"}),
+                json!({"id": 1, "n": "Apple"}),
+                json!({"id": 2, "n": "banana"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn evaluation_equations() {
+        let ids = |filter: &str| {
+            let json = format!(
+                r#"{{"table":"t","columns":["id"],"filter":{},"order":null,"limit":null,"offset":null}}"#,
+                filter
+            );
+            evaluate(&json)
+                .into_iter()
+                .map(|r| r["id"].as_i64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(r#"[{"column":"name","equation":{"Like":"ap%"}}]"#),
+            vec![4]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"name","equation":{"ILike":"ap%"}}]"#),
+            vec![1, 4]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"name","equation":{"Like":"_a%"}}]"#),
+            vec![2]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"name","equation":{"Like":"%\\%%"}}]"#),
+            vec![5]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"id","equation":{"In":[1,3,9]}}]"#),
+            vec![1, 3]
+        );
+        assert_eq!(ids(r#"[{"column":"tag","equation":"IsNull"}]"#), vec![2, 4]);
+        // unknown stays unknown under `NOT`
+        assert_eq!(
+            ids(r#"[{"not":[{"column":"tag","equation":{"Equal":"fruit"}}]}]"#),
+            vec![5]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"tag","equation":{"NotIn":["sale"]}}]"#),
+            vec![1, 3]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"price","equation":{"Column":["Greater","id"]}}]"#),
+            vec![1, 3]
+        );
+
+        let select: Select = serde_json::from_str(
+            r#"{"table":"t","columns":[],"filter":[{"column":"name","equation":{"Regex":"a"}}],"order":null,"limit":null,"offset":null}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            select.evaluate(&rows()),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn like_backtracking() {
+        assert!(like("a_c", "a\\_c", false));
+        assert!(!like("abc", "a\\_c", false));
+        assert!(like("abcbxd", "%b_d", false));
+        assert!(like("", "%%", false));
+        assert!(!like("ab", "a", false));
+
+        // would not finish with recursive backtracking
+        let s = "a".repeat(10_000);
+        assert!(!like(&s, "%a%a%a%a%a%a%a%a%b", false));
+        assert!(like(&s, "%a%a%a%a%a%a%a%a%a", false));
+    }
+
+    #[test]
+    fn evaluation_dates() {
+        let rows = vec![
+            json!({"id": 1, "at": "2024-03-01T09:30:00", "on": "2024-03-01"}),
+            json!({"id": 2, "at": "2024-03-01T18:00:00.5", "on": "2024-03-02"}),
+            json!({"id": 3, "at": "2024-03-01 12:00:00", "on": "2024-02-29"}),
+            json!({"id": 4, "at": "today", "on": null}),
+        ];
+        let ids = |filter: &str| {
+            let json = format!(
+                r#"{{"table":"t","columns":["id"],"filter":{},"order":null,"limit":null,"offset":null}}"#,
+                filter
+            );
+            let select: Select = serde_json::from_str(&json).unwrap();
+            select
+                .evaluate(&rows)
+                .unwrap()
+                .0
+                .into_iter()
+                .map(|r| r.0["id"].as_i64().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            ids(r#"[{"column":"at","equation":{"Greater":{"DateTime":"2024-03-01 10:00:00"}}}]"#),
+            vec![2, 3]
+        );
+        assert_eq!(
+            ids(
+                r#"[{"column":"at","equation":{"Between":[{"DateTime":"2024-03-01 09:30:00"},{"DateTime":"2024-03-01 12:00:00"}]}}]"#
+            ),
+            vec![1, 3]
+        );
+        assert_eq!(
+            ids(r#"[{"column":"on","equation":{"Less":{"Date":"2024-03-01"}}}]"#),
+            vec![3]
+        );
+    }
+}
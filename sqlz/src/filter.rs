@@ -0,0 +1,237 @@
+//! Validates flat filter sequences and normalizes them into boolean trees.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Condition, Conjunction, Expression, Select, SqlzError, SqlzResult};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum FilterErrorKind {
+    /// a nested list or negation without any element
+    Empty,
+    /// a list starts with a conjunction
+    LeadingConjunction,
+    /// a list ends with a conjunction
+    TrailingConjunction,
+    /// two conjunctions next to each other
+    AdjacentConjunctions,
+    /// two conditions next to each other without a conjunction
+    MissingConjunction,
+}
+
+/// an invalid element, located by a JSON pointer such as `/filter/4/0`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FilterError {
+    pub path: String,
+    pub kind: FilterErrorKind,
+}
+
+/// collect errors of a filter, `path` is the JSON pointer of the filter itself.
+/// An empty filter at top level is valid, which means no filtering.
+pub fn filter_errors(filter: &[Expression], path: &str) -> Vec<FilterError> {
+    let mut errors = vec![];
+    walk(filter, path, &mut errors);
+    errors
+}
+
+/// validate a filter, `path` is the JSON pointer of the filter itself
+pub fn validate_filter(filter: &[Expression], path: &str) -> SqlzResult<()> {
+    let errors = filter_errors(filter, path);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(SqlzError::InvalidFilter(errors))
+    }
+}
+
+fn walk(exprs: &[Expression], path: &str, errors: &mut Vec<FilterError>) {
+    let mut expect_operand = true;
+    for (i, e) in exprs.iter().enumerate() {
+        let p = format!("{}/{}", path, i);
+        let error = |kind| FilterError {
+            path: p.clone(),
+            kind,
+        };
+        match e {
+            Expression::Conjunction(_) => {
+                if i == 0 {
+                    errors.push(error(FilterErrorKind::LeadingConjunction));
+                } else if expect_operand {
+                    errors.push(error(FilterErrorKind::AdjacentConjunctions));
+                } else if i == exprs.len() - 1 {
+                    errors.push(error(FilterErrorKind::TrailingConjunction));
+                }
+                expect_operand = true;
+            }
+            operand => {
+                if !expect_operand {
+                    errors.push(error(FilterErrorKind::MissingConjunction));
+                }
+                match operand {
+                    Expression::Nest(v) if v.is_empty() => {
+                        errors.push(error(FilterErrorKind::Empty))
+                    }
+                    Expression::Nest(v) => walk(v, &p, errors),
+                    Expression::Not(n) if n.not.is_empty() => {
+                        errors.push(error(FilterErrorKind::Empty))
+                    }
+                    Expression::Not(n) => walk(&n.not, &format!("{}/not", p), errors),
+                    _ => {}
+                }
+                expect_operand = false;
+            }
+        }
+    }
+}
+
+/// boolean tree of a filter, where `AND` binds tighter than `OR`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum FilterTree {
+    And(Vec<FilterTree>),
+    Or(Vec<FilterTree>),
+    Not(Box<FilterTree>),
+    Condition(Condition),
+    Exists(Box<Select>),
+}
+
+impl FilterTree {
+    /// normalize a valid filter, an empty filter gives `None`
+    pub fn normalize(filter: &[Expression]) -> SqlzResult<Option<FilterTree>> {
+        validate_filter(filter, "")?;
+        if filter.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(build(filter)))
+    }
+}
+
+/// build from a validated, non-empty list
+fn build(exprs: &[Expression]) -> FilterTree {
+    let mut ors = vec![];
+    let mut ands = vec![];
+    for e in exprs.iter() {
+        match e {
+            Expression::Conjunction(Conjunction::AND) => {}
+            Expression::Conjunction(Conjunction::OR) => {
+                ors.push(combine(std::mem::take(&mut ands), true));
+            }
+            Expression::Simple(c) => ands.push(FilterTree::Condition(c.clone())),
+            Expression::Nest(v) => ands.push(build(v)),
+            Expression::Not(n) => ands.push(FilterTree::Not(Box::new(build(&n.not)))),
+            Expression::Exists(e) => ands.push(FilterTree::Exists(e.exists.clone())),
+        }
+    }
+    ors.push(combine(ands, true));
+    combine(ors, false)
+}
+
+/// a single element is returned as is, nested nodes of the same kind are flattened
+fn combine(nodes: Vec<FilterTree>, and: bool) -> FilterTree {
+    if nodes.len() == 1 {
+        return nodes.into_iter().next().unwrap();
+    }
+    let mut flat = vec![];
+    for n in nodes {
+        match n {
+            FilterTree::And(v) if and => flat.extend(v),
+            FilterTree::Or(v) if !and => flat.extend(v),
+            n => flat.push(n),
+        }
+    }
+    if and {
+        FilterTree::And(flat)
+    } else {
+        FilterTree::Or(flat)
+    }
+}
+
+#[cfg(test)]
+mod tests_filter {
+    use super::*;
+    use crate::{DataEnum, Equation};
+
+    fn cond(column: &str) -> Condition {
+        Condition {
+            column: column.to_owned(),
+            equation: Equation::Equal(DataEnum::Integer(1)),
+        }
+    }
+
+    #[test]
+    fn validate() {
+        let json = r#"
+        [
+            "AND",
+            {"column":"c1","equation":{"Equal":1}},
+            "AND",
+            "OR",
+            {"column":"c2","equation":{"Equal":1}},
+            {"column":"c3","equation":{"Equal":1}},
+            "AND",
+            [],
+            "OR",
+            [{"column":"c4","equation":{"Equal":1}}, "AND"],
+            "OR",
+            {"not": []},
+            "AND"
+        ]"#;
+        let filter: Vec<Expression> = serde_json::from_str(json).unwrap();
+
+        let errors = filter_errors(&filter, "/filter");
+
+        let kinds = errors
+            .iter()
+            .map(|e| (e.path.as_str(), e.kind.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("/filter/0", FilterErrorKind::LeadingConjunction),
+                ("/filter/3", FilterErrorKind::AdjacentConjunctions),
+                ("/filter/5", FilterErrorKind::MissingConjunction),
+                ("/filter/7", FilterErrorKind::Empty),
+                ("/filter/9/1", FilterErrorKind::TrailingConjunction),
+                ("/filter/11", FilterErrorKind::Empty),
+                ("/filter/12", FilterErrorKind::TrailingConjunction),
+            ]
+        );
+        assert!(matches!(
+            validate_filter(&filter, "/filter"),
+            Err(SqlzError::InvalidFilter(e)) if e == errors
+        ));
+    }
+
+    #[test]
+    fn normalize() {
+        // c1 OR c2 AND (c3 AND c4) AND NOT (c5 OR c6)
+        let json = r#"
+        [
+            {"column":"c1","equation":{"Equal":1}},
+            "OR",
+            {"column":"c2","equation":{"Equal":1}},
+            "AND",
+            [{"column":"c3","equation":{"Equal":1}}, "AND", {"column":"c4","equation":{"Equal":1}}],
+            "AND",
+            {"not": [{"column":"c5","equation":{"Equal":1}}, "OR", {"column":"c6","equation":{"Equal":1}}]}
+        ]"#;
+        let filter: Vec<Expression> = serde_json::from_str(json).unwrap();
+
+        let tree = FilterTree::normalize(&filter).unwrap();
+
+        assert_eq!(
+            tree,
+            Some(FilterTree::Or(vec![
+                FilterTree::Condition(cond("c1")),
+                FilterTree::And(vec![
+                    FilterTree::Condition(cond("c2")),
+                    FilterTree::Condition(cond("c3")),
+                    FilterTree::Condition(cond("c4")),
+                    FilterTree::Not(Box::new(FilterTree::Or(vec![
+                        FilterTree::Condition(cond("c5")),
+                        FilterTree::Condition(cond("c6")),
+                    ]))),
+                ]),
+            ]))
+        );
+        assert_eq!(FilterTree::normalize(&[]).unwrap(), None);
+    }
+}
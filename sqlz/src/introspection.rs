@@ -0,0 +1,691 @@
+//! Reconstructs a schema from the catalog of a database.
+//!
+//! `IntrospectionQueries` are executed by the caller, whose result rows are
+//! deserialized into `Introspection`, e.g. from JSON objects keyed by column
+//! names, and then turned into a `Schema`.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::parser::table::parse_column_default;
+use crate::{
+    Column, ColumnDefault, ColumnKey, ColumnType, DataEnum, Dialect, ForeignKey, ForeignKeyAction,
+    ForeignKeyDir, Index, Order, OrderType, Schema, SqlzError, SqlzResult, Table, UniqueKey,
+};
+
+/// queries of a dialect, each gives rows of the corresponding `Introspection` field
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IntrospectionQueries {
+    pub columns: String,
+    pub constraints: String,
+    pub indexes: String,
+    pub foreign_keys: String,
+}
+
+/// a column of a table, `data_type` is the native type name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColumnInfo {
+    pub table_name: String,
+    pub column_name: String,
+    pub data_type: String,
+    #[serde(deserialize_with = "bool_or_int")]
+    pub nullable: bool,
+    pub column_default: Option<String>,
+    #[serde(deserialize_with = "bool_or_int")]
+    pub auto_increment: bool,
+}
+
+/// a column of a primary key or unique constraint, `constraint_type` is
+/// `PRIMARY KEY` or `UNIQUE`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConstraintInfo {
+    pub table_name: String,
+    pub constraint_name: String,
+    pub constraint_type: String,
+    pub column_name: String,
+}
+
+/// a column of an index, indexes backing constraints are excluded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexInfo {
+    pub table_name: String,
+    pub index_name: String,
+    pub column_name: String,
+    #[serde(deserialize_with = "bool_or_int")]
+    pub descending: bool,
+}
+
+/// a column of a foreign key, actions are written as in Sql, e.g. `SET NULL`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ForeignKeyInfo {
+    pub table_name: String,
+    pub constraint_name: String,
+    pub column_name: String,
+    pub ref_table: String,
+    pub ref_column: String,
+    pub on_delete: String,
+    pub on_update: String,
+}
+
+/// result rows of `IntrospectionQueries`, in the order given by the queries
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Introspection {
+    pub columns: Vec<ColumnInfo>,
+    pub constraints: Vec<ConstraintInfo>,
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+/// databases such as MySql and Sqlite give booleans as integers
+fn bool_or_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(i64),
+    }
+
+    Ok(match BoolOrInt::deserialize(deserializer)? {
+        BoolOrInt::Bool(b) => b,
+        BoolOrInt::Int(i) => i != 0,
+    })
+}
+
+impl Introspection {
+    /// queries of tables in `schema`, which is a schema in Postgres, a
+    /// database in MySql, and ignored in Sqlite
+    pub fn queries(dialect: Dialect, schema: &str) -> IntrospectionQueries {
+        let schema = dialect.quote_string(schema);
+        match dialect {
+            Dialect::Postgres => postgres_queries(&schema),
+            Dialect::MySql => mysql_queries(&schema),
+            Dialect::Sqlite => sqlite_queries(),
+        }
+    }
+
+    /// build the schema. Single column primary and unique keys become column
+    /// keys, composite ones become table constraints. Defaults which cannot be
+    /// written as `ColumnDefault` are left out.
+    pub fn schema(&self, dialect: Dialect, schema: &str) -> SqlzResult<Schema> {
+        let mut tables: Vec<Table> = vec![];
+        for c in self.columns.iter() {
+            let column = self.column(dialect, c)?;
+            match tables.last_mut() {
+                Some(t) if t.name == c.table_name => t.columns.push(column),
+                _ => tables.push(Table {
+                    name: c.table_name.clone(),
+                    columns: vec![column],
+                    ..Default::default()
+                }),
+            }
+        }
+
+        for (table_name, name, constraint_type, columns) in constraints(&self.constraints) {
+            let table = find_table(&mut tables, table_name)?;
+            let key = match constraint_type {
+                "PRIMARY KEY" => ColumnKey::Primary,
+                "UNIQUE" => ColumnKey::Unique,
+                t => return Err(SqlzError::InvalidValue(format!("constraint type {}", t))),
+            };
+            if columns.len() == 1 {
+                let column = table
+                    .columns
+                    .iter_mut()
+                    .find(|c| c.name == columns[0])
+                    .ok_or_else(|| {
+                        SqlzError::InvalidValue(format!("column {}.{}", table_name, columns[0]))
+                    })?;
+                // a column can be both primary key and unique, primary key wins
+                if column.key != Some(ColumnKey::Primary) {
+                    column.key = Some(key);
+                }
+            } else if key == ColumnKey::Primary {
+                table.primary_key = Some(columns);
+            } else {
+                table
+                    .unique_keys
+                    .get_or_insert_with(Vec::new)
+                    .push(UniqueKey {
+                        name: name.to_owned(),
+                        columns,
+                    });
+            }
+        }
+
+        for fks in group_rows(&self.foreign_keys, |f| (&f.table_name, &f.constraint_name)) {
+            let first = fks[0];
+            let fk = ForeignKey {
+                name: first.constraint_name.clone(),
+                from: ForeignKeyDir {
+                    table: first.table_name.clone(),
+                    columns: fks.iter().map(|f| f.column_name.clone()).collect(),
+                },
+                to: ForeignKeyDir {
+                    table: first.ref_table.clone(),
+                    columns: fks.iter().map(|f| f.ref_column.clone()).collect(),
+                },
+                on_delete: foreign_key_action(&first.on_delete)?,
+                on_update: foreign_key_action(&first.on_update)?,
+            };
+            find_table(&mut tables, &first.table_name)?
+                .foreign_keys
+                .push(fk);
+        }
+
+        let indexes = group_rows(&self.indexes, |i| (&i.table_name, &i.index_name))
+            .into_iter()
+            .map(|rows| Index {
+                name: rows[0].index_name.clone(),
+                table: rows[0].table_name.clone(),
+                columns: rows
+                    .iter()
+                    .map(|r| Order {
+                        name: r.column_name.clone(),
+                        order: if r.descending {
+                            Some(OrderType::Desc)
+                        } else {
+                            None
+                        },
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Schema {
+            schema: schema.to_owned(),
+            tables,
+            indexes: if indexes.is_empty() {
+                None
+            } else {
+                Some(indexes)
+            },
+            views: None,
+        })
+    }
+
+    fn column(&self, dialect: Dialect, info: &ColumnInfo) -> SqlzResult<Column> {
+        let mut column = Column {
+            name: info.column_name.clone(),
+            col_type: dialect.native_column_type(&info.data_type)?,
+            null: Some(info.nullable),
+            key: None,
+            default: None,
+            auto_increment: if info.auto_increment {
+                Some(true)
+            } else {
+                None
+            },
+            comment: None,
+        };
+        if let Some(d) = &info.column_default {
+            match dialect {
+                Dialect::MySql => column.default = mysql_default(d, &column),
+                Dialect::Postgres | Dialect::Sqlite => {
+                    // an unsupported default is left out, the column is kept intact
+                    let mut c = column.clone();
+                    if parse_column_default(d, &mut c).is_ok() {
+                        column = c;
+                    }
+                }
+            }
+        }
+        if column.default == Some(ColumnDefault::Value(DataEnum::Null)) {
+            column.default = None;
+        }
+        Ok(column)
+    }
+}
+
+/// MySql gives literal defaults without quotes, and functions in lower case
+fn mysql_default(d: &str, column: &Column) -> Option<ColumnDefault> {
+    let lower = d.to_lowercase();
+    let function = lower.trim_end_matches("()");
+    if function == "current_timestamp"
+        || function == "now"
+        || lower.starts_with("current_timestamp(")
+    {
+        return Some(ColumnDefault::CurrentTimestamp);
+    }
+    if function == "curdate" || function == "current_date" {
+        return Some(ColumnDefault::CurrentDate);
+    }
+    if function == "curtime" || function == "current_time" {
+        return Some(ColumnDefault::CurrentTime);
+    }
+    if lower == "null" {
+        return None;
+    }
+    let numeric = matches!(
+        column.col_type,
+        ColumnType::SmallInt
+            | ColumnType::Int
+            | ColumnType::BigInt
+            | ColumnType::Float
+            | ColumnType::Double
+            | ColumnType::Decimal(..)
+    );
+    let value = match (numeric, d.parse::<i64>(), d.parse::<f64>()) {
+        (true, Ok(i), _) => DataEnum::Integer(i),
+        (true, _, Ok(f)) => DataEnum::Float(f),
+        // MariaDB quotes string literals
+        _ if d.len() >= 2 && d.starts_with('\'') && d.ends_with('\'') => {
+            DataEnum::String(d[1..d.len() - 1].replace("''", "'"))
+        }
+        _ => DataEnum::String(d.to_owned()),
+    };
+    Some(ColumnDefault::Value(value))
+}
+
+fn foreign_key_action(action: &str) -> SqlzResult<ForeignKeyAction> {
+    match action.to_uppercase().as_str() {
+        "NO ACTION" => Ok(ForeignKeyAction::NoAction),
+        "RESTRICT" => Ok(ForeignKeyAction::Restrict),
+        "CASCADE" => Ok(ForeignKeyAction::Cascade),
+        "SET NULL" => Ok(ForeignKeyAction::SetNull),
+        "SET DEFAULT" => Ok(ForeignKeyAction::SetDefault),
+        a => Err(SqlzError::InvalidValue(format!("foreign key action {}", a))),
+    }
+}
+
+fn find_table<'a>(tables: &'a mut [Table], name: &str) -> SqlzResult<&'a mut Table> {
+    tables
+        .iter_mut()
+        .find(|t| t.name == name)
+        .ok_or_else(|| SqlzError::InvalidValue(format!("table {}", name)))
+}
+
+/// consecutive rows of the same key
+fn group_rows<'a, T, F>(rows: &'a [T], key: F) -> Vec<Vec<&'a T>>
+where
+    F: Fn(&'a T) -> (&'a String, &'a String),
+{
+    let mut res: Vec<Vec<&T>> = vec![];
+    for r in rows.iter() {
+        match res.last_mut() {
+            Some(g) if key(g[0]) == key(r) => g.push(r),
+            _ => res.push(vec![r]),
+        }
+    }
+    res
+}
+
+/// `(table, name, constraint type, columns)` of constraint rows
+fn constraints(rows: &[ConstraintInfo]) -> Vec<(&str, &str, &str, Vec<String>)> {
+    group_rows(rows, |c| (&c.table_name, &c.constraint_name))
+        .into_iter()
+        .map(|g| {
+            (
+                g[0].table_name.as_str(),
+                g[0].constraint_name.as_str(),
+                g[0].constraint_type.as_str(),
+                g.iter().map(|r| r.column_name.clone()).collect(),
+            )
+        })
+        .collect()
+}
+
+fn postgres_queries(schema: &str) -> IntrospectionQueries {
+    let action = |column: &str| {
+        format!(
+            "CASE {} WHEN 'r' THEN 'RESTRICT' WHEN 'c' THEN 'CASCADE' WHEN 'n' THEN 'SET NULL' \
+             WHEN 'd' THEN 'SET DEFAULT' ELSE 'NO ACTION' END",
+            column
+        )
+    };
+    IntrospectionQueries {
+        columns: format!(
+            "SELECT c.relname AS table_name, a.attname AS column_name, \
+             format_type(a.atttypid, a.atttypmod) AS data_type, NOT a.attnotnull AS nullable, \
+             pg_get_expr(d.adbin, d.adrelid) AS column_default, \
+             (a.attidentity <> '' OR coalesce(pg_get_expr(d.adbin, d.adrelid), '') LIKE 'nextval(%') AS auto_increment \
+             FROM pg_catalog.pg_attribute a \
+             JOIN pg_catalog.pg_class c ON c.oid = a.attrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum \
+             WHERE n.nspname = {} AND c.relkind = 'r' AND a.attnum > 0 AND NOT a.attisdropped \
+             ORDER BY c.relname, a.attnum",
+            schema
+        ),
+        constraints: format!(
+            "SELECT c.relname AS table_name, con.conname AS constraint_name, \
+             CASE con.contype WHEN 'p' THEN 'PRIMARY KEY' ELSE 'UNIQUE' END AS constraint_type, \
+             a.attname AS column_name, k.ord AS position \
+             FROM pg_catalog.pg_constraint con \
+             JOIN pg_catalog.pg_class c ON c.oid = con.conrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             CROSS JOIN LATERAL unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord) \
+             JOIN pg_catalog.pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum \
+             WHERE n.nspname = {} AND con.contype IN ('p', 'u') \
+             ORDER BY c.relname, con.conname, k.ord",
+            schema
+        ),
+        indexes: format!(
+            "SELECT t.relname AS table_name, i.relname AS index_name, a.attname AS column_name, \
+             (ix.indoption[k.ord - 1] & 1) = 1 AS descending, k.ord AS position \
+             FROM pg_catalog.pg_index ix \
+             JOIN pg_catalog.pg_class i ON i.oid = ix.indexrelid \
+             JOIN pg_catalog.pg_class t ON t.oid = ix.indrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = t.relnamespace \
+             CROSS JOIN LATERAL unnest(ix.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord) \
+             JOIN pg_catalog.pg_attribute a ON a.attrelid = t.oid AND a.attnum = k.attnum \
+             WHERE n.nspname = {} AND NOT EXISTS \
+             (SELECT 1 FROM pg_catalog.pg_constraint con WHERE con.conindid = ix.indexrelid) \
+             ORDER BY t.relname, i.relname, k.ord",
+            schema
+        ),
+        foreign_keys: format!(
+            "SELECT c.relname AS table_name, con.conname AS constraint_name, a.attname AS column_name, \
+             rc.relname AS ref_table, ra.attname AS ref_column, \
+             {} AS on_delete, {} AS on_update, k.ord AS position \
+             FROM pg_catalog.pg_constraint con \
+             JOIN pg_catalog.pg_class c ON c.oid = con.conrelid \
+             JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace \
+             JOIN pg_catalog.pg_class rc ON rc.oid = con.confrelid \
+             CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, refnum, ord) \
+             JOIN pg_catalog.pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum \
+             JOIN pg_catalog.pg_attribute ra ON ra.attrelid = con.confrelid AND ra.attnum = k.refnum \
+             WHERE n.nspname = {} AND con.contype = 'f' \
+             ORDER BY c.relname, con.conname, k.ord",
+            action("con.confdeltype"),
+            action("con.confupdtype"),
+            schema
+        ),
+    }
+}
+
+fn mysql_queries(schema: &str) -> IntrospectionQueries {
+    IntrospectionQueries {
+        columns: format!(
+            "SELECT c.TABLE_NAME AS table_name, c.COLUMN_NAME AS column_name, \
+             c.COLUMN_TYPE AS data_type, c.IS_NULLABLE = 'YES' AS nullable, \
+             c.COLUMN_DEFAULT AS column_default, c.EXTRA LIKE '%auto_increment%' AS auto_increment \
+             FROM information_schema.COLUMNS c \
+             JOIN information_schema.TABLES t ON t.TABLE_SCHEMA = c.TABLE_SCHEMA AND t.TABLE_NAME = c.TABLE_NAME \
+             WHERE c.TABLE_SCHEMA = {} AND t.TABLE_TYPE = 'BASE TABLE' \
+             ORDER BY c.TABLE_NAME, c.ORDINAL_POSITION",
+            schema
+        ),
+        constraints: format!(
+            "SELECT tc.TABLE_NAME AS table_name, tc.CONSTRAINT_NAME AS constraint_name, \
+             tc.CONSTRAINT_TYPE AS constraint_type, k.COLUMN_NAME AS column_name, \
+             k.ORDINAL_POSITION AS position \
+             FROM information_schema.TABLE_CONSTRAINTS tc \
+             JOIN information_schema.KEY_COLUMN_USAGE k ON k.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA \
+             AND k.TABLE_NAME = tc.TABLE_NAME AND k.CONSTRAINT_NAME = tc.CONSTRAINT_NAME \
+             WHERE tc.TABLE_SCHEMA = {} AND tc.CONSTRAINT_TYPE IN ('PRIMARY KEY', 'UNIQUE') \
+             ORDER BY tc.TABLE_NAME, tc.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+            schema
+        ),
+        indexes: format!(
+            "SELECT s.TABLE_NAME AS table_name, s.INDEX_NAME AS index_name, \
+             s.COLUMN_NAME AS column_name, s.COLLATION = 'D' AS descending, \
+             s.SEQ_IN_INDEX AS position \
+             FROM information_schema.STATISTICS s \
+             WHERE s.TABLE_SCHEMA = {} AND NOT EXISTS \
+             (SELECT 1 FROM information_schema.TABLE_CONSTRAINTS tc WHERE tc.TABLE_SCHEMA = s.TABLE_SCHEMA \
+             AND tc.TABLE_NAME = s.TABLE_NAME AND tc.CONSTRAINT_NAME = s.INDEX_NAME) \
+             ORDER BY s.TABLE_NAME, s.INDEX_NAME, s.SEQ_IN_INDEX",
+            schema
+        ),
+        foreign_keys: format!(
+            "SELECT k.TABLE_NAME AS table_name, k.CONSTRAINT_NAME AS constraint_name, \
+             k.COLUMN_NAME AS column_name, k.REFERENCED_TABLE_NAME AS ref_table, \
+             k.REFERENCED_COLUMN_NAME AS ref_column, r.DELETE_RULE AS on_delete, \
+             r.UPDATE_RULE AS on_update, k.ORDINAL_POSITION AS position \
+             FROM information_schema.REFERENTIAL_CONSTRAINTS r \
+             JOIN information_schema.KEY_COLUMN_USAGE k ON k.CONSTRAINT_SCHEMA = r.CONSTRAINT_SCHEMA \
+             AND k.TABLE_NAME = r.TABLE_NAME AND k.CONSTRAINT_NAME = r.CONSTRAINT_NAME \
+             WHERE r.CONSTRAINT_SCHEMA = {} \
+             ORDER BY k.TABLE_NAME, k.CONSTRAINT_NAME, k.ORDINAL_POSITION",
+            schema
+        ),
+    }
+}
+
+/// Sqlite does not name primary keys and foreign keys, they are named after
+/// the table as `<table>_pkey` and `<table>_fkey<n>`. Indexes created by
+/// `CREATE UNIQUE INDEX` are listed as indexes.
+fn sqlite_queries() -> IntrospectionQueries {
+    let tables = "FROM sqlite_master m";
+    let user_tables = "m.type = 'table' AND m.name NOT LIKE 'sqlite_%'";
+    IntrospectionQueries {
+        columns: format!(
+            "SELECT m.name AS table_name, p.name AS column_name, p.type AS data_type, \
+             NOT p.\"notnull\" AS nullable, p.dflt_value AS column_default, \
+             (p.pk = 1 AND upper(p.type) = 'INTEGER' AND upper(m.sql) LIKE '%AUTOINCREMENT%') AS auto_increment \
+             {} JOIN pragma_table_info(m.name) p \
+             WHERE {} ORDER BY m.name, p.cid",
+            tables, user_tables
+        ),
+        constraints: format!(
+            "SELECT m.name AS table_name, m.name || '_pkey' AS constraint_name, \
+             'PRIMARY KEY' AS constraint_type, p.name AS column_name, p.pk AS position \
+             {} JOIN pragma_table_info(m.name) p WHERE {} AND p.pk > 0 \
+             UNION ALL \
+             SELECT m.name, l.name, 'UNIQUE', i.name, i.seqno + 1 \
+             {} JOIN pragma_index_list(m.name) l JOIN pragma_index_info(l.name) i \
+             WHERE {} AND l.origin = 'u' \
+             ORDER BY table_name, constraint_name, position",
+            tables, user_tables, tables, user_tables
+        ),
+        indexes: format!(
+            "SELECT m.name AS table_name, l.name AS index_name, x.name AS column_name, \
+             x.\"desc\" AS descending, x.seqno + 1 AS position \
+             {} JOIN pragma_index_list(m.name) l JOIN pragma_index_xinfo(l.name) x \
+             WHERE {} AND l.origin = 'c' AND x.key = 1 \
+             ORDER BY m.name, l.name, x.seqno",
+            tables, user_tables
+        ),
+        foreign_keys: format!(
+            "SELECT m.name AS table_name, m.name || '_fkey' || f.id AS constraint_name, \
+             f.\"from\" AS column_name, f.\"table\" AS ref_table, f.\"to\" AS ref_column, \
+             f.on_delete AS on_delete, f.on_update AS on_update, f.seq AS position \
+             {} JOIN pragma_foreign_key_list(m.name) f \
+             WHERE {} ORDER BY m.name, f.id, f.seq",
+            tables, user_tables
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests_introspection {
+    use super::*;
+    use crate::SqlBuilder;
+    use rusqlite::types::ValueRef;
+    use serde_json::{Map, Value};
+
+    fn rows(conn: &rusqlite::Connection, sql: &str) -> Value {
+        let mut stmt = conn.prepare(sql).unwrap();
+        let names = stmt
+            .column_names()
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>();
+        let mut rows = stmt.query(rusqlite::NO_PARAMS).unwrap();
+        let mut res = vec![];
+        while let Some(row) = rows.next().unwrap() {
+            let mut obj = Map::new();
+            for (i, name) in names.iter().enumerate() {
+                let v = match row.get_raw(i) {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(n) => Value::from(n),
+                    ValueRef::Real(f) => Value::from(f),
+                    ValueRef::Text(t) => Value::from(String::from_utf8_lossy(t).to_string()),
+                    ValueRef::Blob(_) => unreachable!(),
+                };
+                obj.insert(name.clone(), v);
+            }
+            res.push(Value::Object(obj));
+        }
+        Value::Array(res)
+    }
+
+    #[test]
+    fn introspect_sqlite() {
+        let json = r#"
+        {
+            "schema": "main",
+            "tables": [
+                {
+                    "name": "groups",
+                    "columns": [
+                        {"name": "id", "col_type": "Int", "null": false, "key": "Primary", "auto_increment": true},
+                        {"name": "name", "col_type": {"VarChar": 50}, "null": false, "key": "Unique"},
+                        {"name": "score", "col_type": {"Decimal": [10, 2]}, "null": true, "key": null, "default": {"Value": 1.5}},
+                        {"name": "created_at", "col_type": "DateTime", "null": false, "key": null, "default": "CurrentTimestamp"}
+                    ]
+                },
+                {
+                    "name": "memberships",
+                    "columns": [
+                        {"name": "group_id", "col_type": "Int", "null": false, "key": null},
+                        {"name": "user_name", "col_type": "Text", "null": false, "key": null},
+                        {"name": "role", "col_type": "Text", "null": true, "key": null, "default": {"Value": "member"}}
+                    ],
+                    "foreign_keys": [
+                        {
+                            "name": "memberships_fkey0",
+                            "from": {"table": "memberships", "columns": ["group_id"]},
+                            "to": {"table": "groups", "columns": ["id"]},
+                            "on_delete": "Cascade",
+                            "on_update": "NoAction"
+                        }
+                    ],
+                    "primary_key": ["group_id", "user_name"]
+                }
+            ],
+            "indexes": [
+                {"name": "idx_role", "table": "memberships", "columns": [{"name": "role", "order": null}, {"name": "user_name", "order": "Desc"}]}
+            ]
+        }"#;
+        let schema: Schema = serde_json::from_str(json).unwrap();
+
+        let path = std::env::temp_dir().join(format!("sqlz_introspect_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        let builder = SqlBuilder::new(Dialect::Sqlite);
+        for t in schema.tables.iter() {
+            for sql in builder.table_create(t).unwrap() {
+                conn.execute_batch(&sql).unwrap();
+            }
+        }
+        for i in schema.indexes.iter().flatten() {
+            for sql in builder.index_create(i).unwrap() {
+                conn.execute_batch(&sql).unwrap();
+            }
+        }
+
+        let queries = Introspection::queries(Dialect::Sqlite, "main");
+        let introspection: Introspection = serde_json::from_value(serde_json::json!({
+            "columns": rows(&conn, &queries.columns),
+            "constraints": rows(&conn, &queries.constraints),
+            "indexes": rows(&conn, &queries.indexes),
+            "foreign_keys": rows(&conn, &queries.foreign_keys),
+        }))
+        .unwrap();
+        let res = introspection.schema(Dialect::Sqlite, "main").unwrap();
+        drop(conn);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(res, schema);
+    }
+
+    #[test]
+    fn native_column_types() {
+        let cases = vec![
+            (
+                Dialect::Postgres,
+                "character varying(20)",
+                ColumnType::VarChar(Some(20)),
+            ),
+            (
+                Dialect::Postgres,
+                "timestamp with time zone",
+                ColumnType::Timestamp,
+            ),
+            (
+                Dialect::Postgres,
+                "timestamp without time zone",
+                ColumnType::DateTime,
+            ),
+            (Dialect::Postgres, "timestamp(3)", ColumnType::DateTime),
+            (Dialect::Postgres, "timestamptz", ColumnType::Timestamp),
+            (Dialect::MySql, "timestamp", ColumnType::Timestamp),
+            (
+                Dialect::Postgres,
+                "numeric(10,2)",
+                ColumnType::Decimal(10, 2),
+            ),
+            (
+                Dialect::Postgres,
+                "integer[]",
+                ColumnType::Array(Box::new(ColumnType::Int)),
+            ),
+            (Dialect::MySql, "tinyint(1)", ColumnType::Bool),
+            (Dialect::MySql, "int(11)", ColumnType::Int),
+            (
+                Dialect::MySql,
+                "enum('a','b')",
+                ColumnType::Enum(vec!["a".to_owned(), "b".to_owned()]),
+            ),
+        ];
+        for (dialect, native, col_type) in cases {
+            assert_eq!(dialect.native_column_type(native), Ok(col_type));
+        }
+        assert!(matches!(
+            Dialect::Postgres.native_column_type("inet"),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn introspect_rows() {
+        let introspection: Introspection = serde_json::from_str(
+            r#"
+        {
+            "columns": [
+                {"table_name": "t", "column_name": "id", "data_type": "int", "nullable": 0, "column_default": null, "auto_increment": 1},
+                {"table_name": "t", "column_name": "qty", "data_type": "int", "nullable": 1, "column_default": "0", "auto_increment": 0},
+                {"table_name": "t", "column_name": "code", "data_type": "varchar(10)", "nullable": 1, "column_default": "a'b", "auto_increment": 0},
+                {"table_name": "t", "column_name": "at", "data_type": "datetime", "nullable": 1, "column_default": "CURRENT_TIMESTAMP", "auto_increment": 0}
+            ],
+            "constraints": [
+                {"table_name": "t", "constraint_name": "PRIMARY", "constraint_type": "PRIMARY KEY", "column_name": "id"},
+                {"table_name": "t", "constraint_name": "uk", "constraint_type": "UNIQUE", "column_name": "qty"},
+                {"table_name": "t", "constraint_name": "uk", "constraint_type": "UNIQUE", "column_name": "code"}
+            ],
+            "indexes": [],
+            "foreign_keys": []
+        }"#,
+        )
+        .unwrap();
+
+        let schema = introspection.schema(Dialect::MySql, "db").unwrap();
+
+        let table = &schema.tables[0];
+        assert_eq!(table.columns[0].key, Some(ColumnKey::Primary));
+        assert_eq!(table.columns[0].auto_increment, Some(true));
+        assert_eq!(
+            table.columns[1].default,
+            Some(ColumnDefault::Value(DataEnum::Integer(0)))
+        );
+        assert_eq!(
+            table.columns[2].default,
+            Some(ColumnDefault::Value(DataEnum::String("a'b".to_owned())))
+        );
+        assert_eq!(
+            table.columns[3].default,
+            Some(ColumnDefault::CurrentTimestamp)
+        );
+        assert_eq!(
+            table.unique_keys,
+            Some(vec![UniqueKey {
+                name: "uk".to_owned(),
+                columns: vec!["qty".to_owned(), "code".to_owned()],
+            }])
+        );
+        assert_eq!(schema.indexes, None);
+    }
+}
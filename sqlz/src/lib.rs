@@ -3,7 +3,67 @@
 //! Sqlz is a package used for defining Sql strings, such as DDL and DML.
 //! Written in Rust data structure, Sqlz provides simplest conversions
 //! from language domain models to JSON, and vice versa.
+//!
+//! ## Builder
+//! - SqlBuilder: renders models into Sql strings of a specific dialect.
+//! - Dialect: Postgres, MySql and Sqlite, which decides identifier quoting
+//!   and string escaping.
+//!
+//! ## Filter
+//! - FilterTree: boolean tree normalized from a flat filter, with filter
+//!   errors located by JSON pointers.
+//!
+//! ## Cursor
+//! - Cursor: keyset pagination of a select, pages are selected after the
+//!   last row of the previous page in `order`.
+//!
+//! ## Evaluate
+//! - Select::evaluate: runs a select against JSON rows in memory.
+//!
+//! ## Parser
+//! - parse: turns Sql text of a select or a create table back into models.
+//!
+//! ## Query string
+//! - Select::from_query_string: compact, URL-safe encoding of a select in a
+//!   query string, in the style of PostgREST.
+//!
+//! ## Introspection
+//! - Introspection: catalog queries per dialect, and their result rows
+//!   assembled into a schema.
+//!
+//! ## Policy
+//! - Policy: readable tables and columns of a schema for untrusted selects,
+//!   with a capped limit, mandatory filters and forbidden operators.
+//!
+//! ## Document
+//! - Schema::to_mermaid, Schema::to_dot and Schema::to_markdown: ER diagrams
+//!   and a data dictionary of a schema.
+//!
+//! ## Migration
+//! - Migration: steps planned from the difference between two schemas.
 
+pub mod builder;
+pub mod cursor;
+pub mod document;
+pub mod error;
+pub mod evaluate;
+pub mod filter;
+pub mod introspection;
+pub mod migration;
 pub mod model;
+pub mod parser;
+pub mod policy;
+pub mod query_string;
 
+pub use builder::{Dialect, SqlBuilder};
+pub use cursor::Cursor;
+pub use error::{SqlzError, SqlzResult};
+pub use filter::{FilterError, FilterErrorKind, FilterTree};
+pub use introspection::{Introspection, IntrospectionQueries};
+pub use migration::Migration;
 pub use model::*;
+pub use parser::{parse, parse_select, parse_table_create, ParseError, Statement};
+pub use policy::{Operator, Policy, PolicyViolation, PolicyViolationKind, TablePolicy};
+
+#[cfg(feature = "derive")]
+pub use sqlz_derive::SqlzTable;
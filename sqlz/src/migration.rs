@@ -0,0 +1,742 @@
+//! Computes a migration plan between two schemas.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Column, ColumnAdd, ColumnAlias, ColumnAlterCase, ColumnDrop, ColumnExpr, ColumnKey,
+    ColumnModify, Condition, Equation, Expression, ForeignKeyCreate, ForeignKeyDrop, Function,
+    Index, IndexCreate, IndexDrop, Order, Schema, Select, SqlzError, SqlzResult, Table, TableAlter,
+    TableCreate, TableDrop, TableSource, View, ViewDrop,
+};
+
+/// a single step of a migration plan
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Migration {
+    TableCreate(TableCreate),
+    TableAlter(TableAlter),
+    TableDrop(TableDrop),
+    IndexCreate(IndexCreate),
+    IndexDrop(IndexDrop),
+    ForeignKeyCreate(ForeignKeyCreate),
+    ForeignKeyDrop(ForeignKeyDrop),
+    ViewCreate(View),
+    ViewDrop(ViewDrop),
+}
+
+impl Migration {
+    /// plan the steps turning schema `from` into schema `to`, in the order of:
+    /// view drops, foreign key drops, index drops, table alters, table drops,
+    /// table creates, index creates, foreign key creates and view creates.
+    /// Foreign keys are always dropped before their tables, and a created table
+    /// comes after the table it refers. A changed view is dropped and created,
+    /// and so is a view referring to an altered or dropped table or to another
+    /// such view. Views are dropped in reverse order and created in order of
+    /// the schema.
+    /// Renaming is not detected, a renamed column is dropped and added.
+    /// A `Multiple` key column gets its index created or dropped, other changes
+    /// of primary keys, unique keys or checks of a table are `Unsupported`. The
+    /// comment of a table is not compared, those of columns are.
+    pub fn plan(from: &Schema, to: &Schema) -> SqlzResult<Vec<Migration>> {
+        let dropped = from
+            .tables
+            .iter()
+            .filter(|t| find(&to.tables, &t.name).is_none())
+            .collect::<Vec<_>>();
+        let created = to
+            .tables
+            .iter()
+            .filter(|t| find(&from.tables, &t.name).is_none())
+            .collect::<Vec<_>>();
+        let common = to
+            .tables
+            .iter()
+            .filter_map(|t| find(&from.tables, &t.name).map(|f| (f, t)))
+            .collect::<Vec<_>>();
+        for (f, t) in common.iter() {
+            check_keys(f, t)?;
+        }
+
+        let mut fk_drops = vec![];
+        let mut fk_creates = vec![];
+        for t in dropped.iter() {
+            fk_drops.extend(t.foreign_keys.iter().map(foreign_key_drop));
+        }
+        for (f, t) in common.iter() {
+            for fk in f.foreign_keys.iter() {
+                if !t.foreign_keys.contains(fk) {
+                    fk_drops.push(foreign_key_drop(fk));
+                }
+            }
+            for fk in t.foreign_keys.iter() {
+                if !f.foreign_keys.contains(fk) {
+                    fk_creates.push(Migration::ForeignKeyCreate(fk.clone()));
+                }
+            }
+        }
+
+        let dropped_names = dropped
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<HashSet<_>>();
+        let mut from_indexes = from.indexes.clone().unwrap_or_default();
+        let mut to_indexes = to.indexes.clone().unwrap_or_default();
+        for (f, t) in common.iter() {
+            from_indexes.extend(key_indexes(f));
+            to_indexes.extend(key_indexes(t));
+        }
+
+        let mut index_drops = vec![];
+        let mut index_creates = vec![];
+        for i in from_indexes.iter() {
+            if dropped_names.contains(i.table.as_str()) {
+                continue;
+            }
+            if find_index(&to_indexes, &i.name) != Some(i) {
+                index_drops.push(Migration::IndexDrop(IndexDrop {
+                    name: i.name.clone(),
+                    table: i.table.clone(),
+                }));
+            }
+        }
+        for i in to_indexes.iter() {
+            if find_index(&from_indexes, &i.name) != Some(i) {
+                index_creates.push(Migration::IndexCreate(i.clone()));
+            }
+        }
+
+        let alters = common
+            .iter()
+            .filter_map(|(f, t)| table_alter(f, t))
+            .map(Migration::TableAlter)
+            .collect::<Vec<_>>();
+
+        let table_drops = dropped.iter().map(|t| {
+            Migration::TableDrop(TableDrop {
+                name: t.name.clone(),
+            })
+        });
+
+        let (table_creates, deferred_fks) = table_creates(&created, &common);
+        fk_creates.extend(deferred_fks);
+
+        let from_views = from.views.as_deref().unwrap_or_default();
+        let to_views = to.views.as_deref().unwrap_or_default();
+        // a changed view is rebuilt, and so is a view referring to an altered
+        // or dropped table or to a rebuilt view
+        let mut rebuilt = dropped
+            .iter()
+            .map(|t| t.name.as_str())
+            .chain(alters.iter().filter_map(|m| match m {
+                Migration::TableAlter(a) => Some(a.name.as_str()),
+                _ => None,
+            }))
+            .map(unqualified)
+            .collect::<HashSet<_>>();
+        let referred = from_views
+            .iter()
+            .map(|v| {
+                let mut tables = HashSet::new();
+                select_tables(&v.select, &mut tables);
+                tables
+            })
+            .collect::<Vec<_>>();
+        let mut rebuilt_views = HashSet::new();
+        loop {
+            let before = rebuilt_views.len();
+            for (v, tables) in from_views.iter().zip(referred.iter()) {
+                let changed = find_view(to_views, &v.name) != Some(v);
+                if changed || tables.iter().any(|t| rebuilt.contains(unqualified(t))) {
+                    rebuilt_views.insert(v.name.as_str());
+                    rebuilt.insert(unqualified(&v.name));
+                }
+            }
+            if rebuilt_views.len() == before {
+                break;
+            }
+        }
+        let view_drops = from_views
+            .iter()
+            .rev()
+            .filter(|v| rebuilt_views.contains(v.name.as_str()))
+            .map(|v| {
+                Migration::ViewDrop(ViewDrop {
+                    name: v.name.clone(),
+                    materialized: v.materialized,
+                })
+            });
+        let view_creates = to_views
+            .iter()
+            .filter(|v| {
+                rebuilt_views.contains(v.name.as_str()) || find_view(from_views, &v.name).is_none()
+            })
+            .cloned()
+            .map(Migration::ViewCreate);
+
+        let mut plan = view_drops.collect::<Vec<_>>();
+        plan.extend(fk_drops);
+        plan.extend(index_drops);
+        plan.extend(alters);
+        plan.extend(table_drops);
+        plan.extend(table_creates);
+        plan.extend(index_creates);
+        plan.extend(fk_creates);
+        plan.extend(view_creates);
+        Ok(plan)
+    }
+}
+
+fn find<'a>(tables: &'a [Table], name: &str) -> Option<&'a Table> {
+    tables.iter().find(|t| t.name == name)
+}
+
+fn find_index<'a>(indexes: &'a [Index], name: &str) -> Option<&'a Index> {
+    indexes.iter().find(|i| i.name == name)
+}
+
+fn find_view<'a>(views: &'a [View], name: &str) -> Option<&'a View> {
+    views.iter().find(|v| v.name == name)
+}
+
+fn find_column<'a>(columns: &'a [Column], name: &str) -> Option<&'a Column> {
+    columns.iter().find(|c| c.name == name)
+}
+
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// names of tables and views a select refers to, also in joins, common table
+/// expressions, subqueries and set operations
+fn select_tables(select: &Select, tables: &mut HashSet<String>) {
+    for cte in select.with.iter().flatten() {
+        select_tables(&cte.select, tables);
+    }
+    source_tables(&select.table, tables);
+    for join in select.join.iter().flatten() {
+        source_tables(&join.table, tables);
+        expression_tables(join.on.as_deref().unwrap_or_default(), tables);
+    }
+    for c in select.columns.iter() {
+        if let ColumnAlias::Computed(c) = c {
+            column_expr_tables(&c.expr, tables);
+        }
+    }
+    expression_tables(select.filter.as_deref().unwrap_or_default(), tables);
+    expression_tables(select.having.as_deref().unwrap_or_default(), tables);
+    for op in select.set_operations.iter().flatten() {
+        select_tables(op.parts().1, tables);
+    }
+}
+
+fn source_tables(source: &TableSource, tables: &mut HashSet<String>) {
+    match source {
+        TableSource::Table(t) => {
+            tables.insert(t.clone());
+        }
+        TableSource::Derived(d) => select_tables(&d.select, tables),
+    }
+}
+
+fn expression_tables(exprs: &[Expression], tables: &mut HashSet<String>) {
+    for e in exprs.iter() {
+        match e {
+            Expression::Simple(Condition {
+                equation: Equation::InSelect(s) | Equation::NotInSelect(s),
+                ..
+            }) => select_tables(s, tables),
+            Expression::Nest(v) => expression_tables(v, tables),
+            Expression::Not(n) => expression_tables(&n.not, tables),
+            Expression::Exists(e) => select_tables(&e.exists, tables),
+            _ => {}
+        }
+    }
+}
+
+/// subqueries of a computed column are in `CASE` conditions
+fn column_expr_tables(expr: &ColumnExpr, tables: &mut HashSet<String>) {
+    match expr {
+        ColumnExpr::Column(_) | ColumnExpr::Value(_) => {}
+        ColumnExpr::Arithmetic(a) => {
+            column_expr_tables(&a.0, tables);
+            column_expr_tables(&a.2, tables);
+        }
+        ColumnExpr::Case(c) => {
+            for w in c.when.iter() {
+                expression_tables(&w.condition, tables);
+                column_expr_tables(&w.then, tables);
+            }
+            if let Some(e) = &c.otherwise {
+                column_expr_tables(e, tables);
+            }
+        }
+        ColumnExpr::Coalesce(v) | ColumnExpr::Function(Function::Concat(v)) => {
+            for e in v.iter() {
+                column_expr_tables(e, tables);
+            }
+        }
+        ColumnExpr::Cast(c) => column_expr_tables(&c.0, tables),
+        ColumnExpr::Function(f) => match f {
+            Function::Upper(e) | Function::Lower(e) | Function::Trim(e) | Function::Length(e) => {
+                column_expr_tables(e, tables)
+            }
+            Function::Substring(s) => column_expr_tables(&s.0, tables),
+            Function::Extract(x) | Function::DateTrunc(x) => column_expr_tables(&x.1, tables),
+            Function::Concat(_) | Function::CurrentTimestamp => {}
+        },
+    }
+}
+
+fn foreign_key_drop(fk: &ForeignKeyCreate) -> Migration {
+    Migration::ForeignKeyDrop(ForeignKeyDrop {
+        name: fk.name.clone(),
+        table: fk.from.table.clone(),
+    })
+}
+
+fn table_alter(from: &Table, to: &Table) -> Option<TableAlter> {
+    let mut alter = vec![];
+    for c in from.columns.iter() {
+        if find_column(&to.columns, &c.name).is_none() {
+            alter.push(ColumnAlterCase::Drop(ColumnDrop {
+                name: c.name.clone(),
+            }));
+        }
+    }
+    for c in to.columns.iter() {
+        match find_column(&from.columns, &c.name) {
+            None => alter.push(ColumnAlterCase::Add(ColumnAdd::clone(c))),
+            Some(f) if !same_column(f, c) => {
+                alter.push(ColumnAlterCase::Modify(ColumnModify::clone(c)))
+            }
+            Some(_) => {}
+        }
+    }
+
+    if alter.is_empty() {
+        None
+    } else {
+        Some(TableAlter {
+            name: to.name.clone(),
+            alter,
+        })
+    }
+}
+
+/// columns equal apart from their keys, which are compared by `check_keys`
+/// and `key_indexes`
+fn same_column(from: &Column, to: &Column) -> bool {
+    Column {
+        key: None,
+        ..from.clone()
+    } == Column {
+        key: None,
+        ..to.clone()
+    }
+}
+
+/// primary keys, unique keys and checks of a table can not be altered, only a
+/// column added as unique key is written with its key
+fn check_keys(from: &Table, to: &Table) -> SqlzResult<()> {
+    let unique = |t: &Table| {
+        t.columns
+            .iter()
+            .filter(|c| c.key == Some(ColumnKey::Unique))
+            .filter(|c| find_column(&from.columns, &c.name).is_some())
+            .filter(|c| find_column(&to.columns, &c.name).is_some())
+            .map(|c| c.name.clone())
+            .collect::<Vec<_>>()
+    };
+    let changed = if from.primary_key_columns() != to.primary_key_columns() {
+        "primary key"
+    } else if unique(from) != unique(to) || from.unique_keys != to.unique_keys {
+        "unique keys"
+    } else if from.checks != to.checks {
+        "checks"
+    } else {
+        return Ok(());
+    };
+    Err(SqlzError::Unsupported(format!(
+        "altering {} of table {}",
+        changed, to.name
+    )))
+}
+
+/// indexes of `Multiple` key columns, named as in `CREATE TABLE`
+fn key_indexes(table: &Table) -> Vec<Index> {
+    table
+        .columns
+        .iter()
+        .filter(|c| c.key == Some(ColumnKey::Multiple))
+        .map(|c| Index {
+            name: format!("{}_{}_index", table.name, c.name),
+            table: table.name.clone(),
+            columns: vec![Order {
+                name: c.name.clone(),
+                order: None,
+            }],
+        })
+        .collect()
+}
+
+/// order created tables so that a referred table comes first. Tables in a
+/// reference cycle are created without foreign key, which is added afterwards.
+fn table_creates(
+    created: &[&Table],
+    common: &[(&Table, &Table)],
+) -> (Vec<Migration>, Vec<Migration>) {
+    let mut ready = common
+        .iter()
+        .map(|(_, t)| t.name.as_str())
+        .collect::<HashSet<_>>();
+    let mut pending = created.to_vec();
+    let mut creates = vec![];
+    let mut deferred = vec![];
+
+    while !pending.is_empty() {
+        let (now, later): (Vec<&Table>, Vec<&Table>) = pending.iter().partition(|t| {
+            t.foreign_keys
+                .iter()
+                .all(|fk| fk.to.table == t.name || ready.contains(fk.to.table.as_str()))
+        });
+
+        if now.is_empty() {
+            for t in later {
+                let mut table = TableCreate::clone(t);
+                let fks = std::mem::take(&mut table.foreign_keys);
+                deferred.extend(fks.into_iter().map(Migration::ForeignKeyCreate));
+                creates.push(Migration::TableCreate(table));
+            }
+            break;
+        }
+
+        for t in now {
+            ready.insert(t.name.as_str());
+            creates.push(Migration::TableCreate(TableCreate::clone(t)));
+        }
+        pending = later;
+    }
+
+    (creates, deferred)
+}
+
+#[cfg(test)]
+mod tests_migration {
+    use super::*;
+    use crate::{ColumnType, Dialect, ForeignKey, ForeignKeyDir, SqlBuilder};
+
+    fn column(name: &str, col_type: ColumnType) -> Column {
+        Column {
+            name: name.to_owned(),
+            col_type,
+            ..Default::default()
+        }
+    }
+
+    fn foreign_key(name: &str, from: &str, to: &str) -> ForeignKey {
+        ForeignKey {
+            name: name.to_owned(),
+            from: ForeignKeyDir {
+                table: from.to_owned(),
+                columns: vec![format!("{}_id", to)],
+            },
+            to: ForeignKeyDir {
+                table: to.to_owned(),
+                columns: vec!["id".to_owned()],
+            },
+            on_delete: Default::default(),
+            on_update: Default::default(),
+        }
+    }
+
+    fn table(name: &str, columns: Vec<Column>, foreign_key: Option<ForeignKey>) -> Table {
+        Table {
+            name: name.to_owned(),
+            columns,
+            foreign_keys: foreign_key.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plan() {
+        let from = Schema {
+            schema: "dev".to_owned(),
+            tables: vec![
+                table("users", vec![column("id", ColumnType::Int)], None),
+                table(
+                    "posts",
+                    vec![
+                        column("id", ColumnType::Int),
+                        column("users_id", ColumnType::Int),
+                        column("title", ColumnType::VarChar(None)),
+                    ],
+                    Some(foreign_key("fk_posts_users", "posts", "users")),
+                ),
+                table(
+                    "logs",
+                    vec![
+                        column("id", ColumnType::Int),
+                        column("users_id", ColumnType::Int),
+                    ],
+                    Some(foreign_key("fk_logs_users", "logs", "users")),
+                ),
+            ],
+            indexes: Some(vec![
+                Index {
+                    name: "idx_logs".to_owned(),
+                    table: "logs".to_owned(),
+                    columns: vec![Order {
+                        name: "id".to_owned(),
+                        order: None,
+                    }],
+                },
+                Index {
+                    name: "idx_posts".to_owned(),
+                    table: "posts".to_owned(),
+                    columns: vec![Order {
+                        name: "title".to_owned(),
+                        order: None,
+                    }],
+                },
+            ]),
+            views: None,
+        };
+        let to = Schema {
+            schema: "dev".to_owned(),
+            tables: vec![
+                table(
+                    "users",
+                    vec![
+                        column("id", ColumnType::Int),
+                        column("name", ColumnType::Text),
+                    ],
+                    None,
+                ),
+                table(
+                    "posts",
+                    vec![
+                        column("id", ColumnType::Int),
+                        column("users_id", ColumnType::Int),
+                        column("title", ColumnType::Text),
+                        column("tags_id", ColumnType::Int),
+                    ],
+                    Some(foreign_key("fk_posts_tags", "posts", "tags")),
+                ),
+                table(
+                    "comments",
+                    vec![column("id", ColumnType::Int)],
+                    Some(foreign_key("fk_comments_tags", "comments", "tags")),
+                ),
+                table("tags", vec![column("id", ColumnType::Int)], None),
+            ],
+            indexes: None,
+            views: None,
+        };
+
+        let plan = Migration::plan(&from, &to).unwrap();
+
+        assert_eq!(
+            plan,
+            vec![
+                Migration::ForeignKeyDrop(IndexDrop {
+                    name: "fk_logs_users".to_owned(),
+                    table: "logs".to_owned(),
+                }),
+                Migration::ForeignKeyDrop(IndexDrop {
+                    name: "fk_posts_users".to_owned(),
+                    table: "posts".to_owned(),
+                }),
+                Migration::IndexDrop(IndexDrop {
+                    name: "idx_posts".to_owned(),
+                    table: "posts".to_owned(),
+                }),
+                Migration::TableAlter(TableAlter {
+                    name: "users".to_owned(),
+                    alter: vec![ColumnAlterCase::Add(column("name", ColumnType::Text))],
+                }),
+                Migration::TableAlter(TableAlter {
+                    name: "posts".to_owned(),
+                    alter: vec![
+                        ColumnAlterCase::Modify(column("title", ColumnType::Text)),
+                        ColumnAlterCase::Add(column("tags_id", ColumnType::Int)),
+                    ],
+                }),
+                Migration::TableDrop(TableDrop {
+                    name: "logs".to_owned(),
+                }),
+                Migration::TableCreate(table("tags", vec![column("id", ColumnType::Int)], None)),
+                Migration::TableCreate(table(
+                    "comments",
+                    vec![column("id", ColumnType::Int)],
+                    Some(foreign_key("fk_comments_tags", "comments", "tags")),
+                )),
+                Migration::ForeignKeyCreate(foreign_key("fk_posts_tags", "posts", "tags")),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_cycle() {
+        let from = Schema {
+            schema: "dev".to_owned(),
+            tables: vec![],
+            indexes: None,
+            views: None,
+        };
+        let to = Schema {
+            schema: "dev".to_owned(),
+            tables: vec![
+                table(
+                    "a",
+                    vec![column("b_id", ColumnType::Int)],
+                    Some(foreign_key("fk_a_b", "a", "b")),
+                ),
+                table(
+                    "b",
+                    vec![column("a_id", ColumnType::Int)],
+                    Some(foreign_key("fk_b_a", "b", "a")),
+                ),
+            ],
+            indexes: None,
+            views: None,
+        };
+
+        let plan = Migration::plan(&from, &to).unwrap();
+
+        assert_eq!(
+            plan,
+            vec![
+                Migration::TableCreate(table("a", vec![column("b_id", ColumnType::Int)], None)),
+                Migration::TableCreate(table("b", vec![column("a_id", ColumnType::Int)], None)),
+                Migration::ForeignKeyCreate(foreign_key("fk_a_b", "a", "b")),
+                Migration::ForeignKeyCreate(foreign_key("fk_b_a", "b", "a")),
+            ]
+        );
+        assert_eq!(Migration::plan(&to, &to).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn plan_keys() {
+        let keyed = |name: &str, key: Option<ColumnKey>| Column {
+            key,
+            ..column(name, ColumnType::Int)
+        };
+        let schema = |columns| Schema {
+            schema: "dev".to_owned(),
+            tables: vec![table("users", columns, None)],
+            indexes: None,
+            views: None,
+        };
+        let from = schema(vec![
+            keyed("id", Some(ColumnKey::Primary)),
+            keyed("age", Some(ColumnKey::Multiple)),
+            keyed("score", None),
+        ]);
+        let to = schema(vec![
+            keyed("id", Some(ColumnKey::Primary)),
+            keyed("age", None),
+            keyed("score", Some(ColumnKey::Multiple)),
+        ]);
+
+        let plan = Migration::plan(&from, &to).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).migration(&plan).unwrap(),
+            vec![
+                r#"DROP INDEX "users_age_index""#,
+                r#"CREATE INDEX "users_score_index" ON "users" ("score")"#,
+            ]
+        );
+
+        let to = schema(vec![
+            keyed("id", None),
+            keyed("age", Some(ColumnKey::Multiple)),
+            keyed("score", None),
+        ]);
+        assert!(matches!(
+            Migration::plan(&from, &to),
+            Err(SqlzError::Unsupported(_))
+        ));
+
+        let mut to = from.clone();
+        to.tables[0].columns[2].key = Some(ColumnKey::Unique);
+        assert!(matches!(
+            Migration::plan(&from, &to),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn plan_views() {
+        let view = |name: &str, table: &str, limit: u64, materialized: Option<bool>| {
+            let json = format!(
+                r#"{{"name": "{}", "select": {{"table": "{}", "columns": ["id"], "filter": null, "order": null, "limit": {}, "offset": null}}}}"#,
+                name, table, limit
+            );
+            View {
+                materialized,
+                ..serde_json::from_str(&json).unwrap()
+            }
+        };
+        let schema = |views| Schema {
+            schema: "dev".to_owned(),
+            tables: vec![
+                table("users", vec![column("id", ColumnType::Int)], None),
+                table("orders", vec![column("id", ColumnType::Int)], None),
+            ],
+            indexes: None,
+            views: Some(views),
+        };
+        let from = schema(vec![
+            view("kept", "users", 1, None),
+            view("changed", "users", 1, Some(true)),
+            view("dropped", "users", 1, None),
+            view("unrelated", "orders", 1, Some(true)),
+            view("nested", "kept", 1, None),
+        ]);
+        let to = schema(vec![
+            view("kept", "users", 1, None),
+            view("changed", "users", 2, Some(true)),
+            view("unrelated", "orders", 1, Some(true)),
+            view("nested", "kept", 1, None),
+            view("created", "users", 1, None),
+        ]);
+
+        let plan = Migration::plan(&from, &to).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).migration(&plan).unwrap(),
+            vec![
+                r#"DROP VIEW "dropped""#,
+                r#"DROP MATERIALIZED VIEW "changed""#,
+                r#"CREATE MATERIALIZED VIEW "changed" AS SELECT "id" FROM "users" LIMIT 2"#,
+                r#"CREATE VIEW "created" AS SELECT "id" FROM "users" LIMIT 1"#,
+            ]
+        );
+
+        // views over an altered table, also through another view, are rebuilt
+        // and a view over another table is left alone
+        let mut altered = from.clone();
+        altered.tables[0]
+            .columns
+            .push(column("name", ColumnType::Text));
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .migration(&Migration::plan(&from, &altered).unwrap())
+                .unwrap(),
+            vec![
+                r#"DROP VIEW "nested""#,
+                r#"DROP VIEW "dropped""#,
+                r#"DROP MATERIALIZED VIEW "changed""#,
+                r#"DROP VIEW "kept""#,
+                r#"ALTER TABLE "users" ADD COLUMN "name" TEXT"#,
+                r#"CREATE VIEW "kept" AS SELECT "id" FROM "users" LIMIT 1"#,
+                r#"CREATE MATERIALIZED VIEW "changed" AS SELECT "id" FROM "users" LIMIT 1"#,
+                r#"CREATE VIEW "dropped" AS SELECT "id" FROM "users" LIMIT 1"#,
+                r#"CREATE VIEW "nested" AS SELECT "id" FROM "kept" LIMIT 1"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::SerializeTupleVariant;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// column type, variant can have specific size, e.g.: VarChar(Some(255)).
+///
+/// In JSON, `Char(None)` and `VarChar(None)` are written as plain `"Char"`
+/// and `"VarChar"`, same as the other unit-like variants, while sized variants
+/// are written as `{"VarChar":255}`, `{"Decimal":[10,2]}`, `{"Enum":["a","b"]}`
+/// or `{"Array":"Int"}`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnType {
+    Bool,
+    SmallInt,
+    Int,
+    BigInt,
+    Float,
+    Double,
+    Decimal(u32, u32),
+    Date,
+    Time,
+    DateTime,
+    Timestamp,
+    Char(Option<u32>),
+    VarChar(Option<u32>),
+    Text,
+    Json,
+    Binary,
+    Uuid,
+    Enum(Vec<String>),
+    Array(Box<ColumnType>),
+}
+
+impl Default for ColumnType {
+    fn default() -> Self {
+        ColumnType::VarChar(None)
+    }
+}
+
+const NAME: &str = "ColumnType";
+
+const VARIANTS: &[&str] = &[
+    "Bool",
+    "SmallInt",
+    "Int",
+    "BigInt",
+    "Float",
+    "Double",
+    "Decimal",
+    "Date",
+    "Time",
+    "DateTime",
+    "Timestamp",
+    "Char",
+    "VarChar",
+    "Text",
+    "Json",
+    "Binary",
+    "Uuid",
+    "Enum",
+    "Array",
+];
+
+impl ColumnType {
+    /// variant name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColumnType::Bool => "Bool",
+            ColumnType::SmallInt => "SmallInt",
+            ColumnType::Int => "Int",
+            ColumnType::BigInt => "BigInt",
+            ColumnType::Float => "Float",
+            ColumnType::Double => "Double",
+            ColumnType::Decimal(..) => "Decimal",
+            ColumnType::Date => "Date",
+            ColumnType::Time => "Time",
+            ColumnType::DateTime => "DateTime",
+            ColumnType::Timestamp => "Timestamp",
+            ColumnType::Char(_) => "Char",
+            ColumnType::VarChar(_) => "VarChar",
+            ColumnType::Text => "Text",
+            ColumnType::Json => "Json",
+            ColumnType::Binary => "Binary",
+            ColumnType::Uuid => "Uuid",
+            ColumnType::Enum(_) => "Enum",
+            ColumnType::Array(_) => "Array",
+        }
+    }
+
+    fn from_unit_name(name: &str) -> Option<Self> {
+        let t = match name {
+            "Bool" => ColumnType::Bool,
+            "SmallInt" => ColumnType::SmallInt,
+            "Int" => ColumnType::Int,
+            "BigInt" => ColumnType::BigInt,
+            "Float" => ColumnType::Float,
+            "Double" => ColumnType::Double,
+            "Date" => ColumnType::Date,
+            "Time" => ColumnType::Time,
+            "DateTime" => ColumnType::DateTime,
+            "Timestamp" => ColumnType::Timestamp,
+            "Char" => ColumnType::Char(None),
+            "VarChar" => ColumnType::VarChar(None),
+            "Text" => ColumnType::Text,
+            "Json" => ColumnType::Json,
+            "Binary" => ColumnType::Binary,
+            "Uuid" => ColumnType::Uuid,
+            _ => return None,
+        };
+        Some(t)
+    }
+}
+
+impl Serialize for ColumnType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let name = self.name();
+        let index = VARIANTS.iter().position(|v| *v == name).unwrap_or_default() as u32;
+        match self {
+            ColumnType::Char(Some(n)) | ColumnType::VarChar(Some(n)) => {
+                serializer.serialize_newtype_variant(NAME, index, name, n)
+            }
+            ColumnType::Decimal(p, s) => {
+                let mut tv = serializer.serialize_tuple_variant(NAME, index, name, 2)?;
+                tv.serialize_field(p)?;
+                tv.serialize_field(s)?;
+                tv.end()
+            }
+            ColumnType::Enum(v) => serializer.serialize_newtype_variant(NAME, index, name, v),
+            ColumnType::Array(t) => serializer.serialize_newtype_variant(NAME, index, name, t),
+            _ => serializer.serialize_unit_variant(NAME, index, name),
+        }
+    }
+}
+
+struct ColumnTypeVisitor;
+
+impl<'de> Visitor<'de> for ColumnTypeVisitor {
+    type Value = ColumnType;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a column type name or a single-key map of a sized column type")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        ColumnType::from_unit_name(v).ok_or_else(|| de::Error::unknown_variant(v, VARIANTS))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let key: String = map
+            .next_key()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let t = match key.as_str() {
+            "Char" => ColumnType::Char(map.next_value()?),
+            "VarChar" => ColumnType::VarChar(map.next_value()?),
+            "Decimal" => {
+                let (p, s) = map.next_value()?;
+                ColumnType::Decimal(p, s)
+            }
+            "Enum" => ColumnType::Enum(map.next_value()?),
+            "Array" => ColumnType::Array(Box::new(map.next_value()?)),
+            _ => return Err(de::Error::unknown_variant(&key, VARIANTS)),
+        };
+        if map.next_key::<String>()?.is_some() {
+            return Err(de::Error::invalid_length(2, &self));
+        }
+        Ok(t)
+    }
+}
+
+impl<'de> Deserialize<'de> for ColumnType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ColumnTypeVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests_column_type {
+    use super::*;
+
+    #[test]
+    fn convert() {
+        let types = vec![
+            ColumnType::Int,
+            ColumnType::VarChar(None),
+            ColumnType::VarChar(Some(255)),
+            ColumnType::Char(Some(2)),
+            ColumnType::Decimal(10, 2),
+            ColumnType::Uuid,
+            ColumnType::Enum(vec!["a".to_owned(), "b".to_owned()]),
+            ColumnType::Array(Box::new(ColumnType::BigInt)),
+        ];
+
+        let cvt = serde_json::to_string(&types).unwrap();
+
+        let res = "[\"Int\",\"VarChar\",{\"VarChar\":255},{\"Char\":2},{\"Decimal\":[10,2]},\"Uuid\",{\"Enum\":[\"a\",\"b\"]},{\"Array\":\"BigInt\"}]";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Vec<ColumnType> = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, types);
+    }
+
+    #[test]
+    fn legacy() {
+        let deserialized: Vec<ColumnType> =
+            serde_json::from_str(r#"["Char","VarChar","Text",{"VarChar":null}]"#).unwrap();
+
+        assert_eq!(
+            deserialized,
+            vec![
+                ColumnType::Char(None),
+                ColumnType::VarChar(None),
+                ColumnType::Text,
+                ColumnType::VarChar(None),
+            ]
+        );
+
+        assert!(serde_json::from_str::<ColumnType>(r#""Integer""#).is_err());
+        assert!(serde_json::from_str::<ColumnType>(r#"{"Int":1}"#).is_err());
+    }
+}
@@ -1,59 +1,94 @@
+use std::convert::TryFrom;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::ColumnType;
+use crate::{Computed, Expression, Select, Window};
 
 /// column key type
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub enum ColumnKey {
+    #[default]
     NotKey,
     Primary,
     Unique,
     Multiple,
 }
 
-impl Default for ColumnKey {
-    fn default() -> Self {
-        ColumnKey::NotKey
-    }
-}
-
-/// column type, variant can have specific size, e.g.: Int(i32)
+/// column default value, either a literal or the current date/time
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub enum ColumnType {
-    Bool,
-    Int,
-    Float,
-    Double,
-    Date,
-    Time,
-    DateTime,
-    Timestamp,
-    Char,
-    VarChar,
-    Text,
-    Json,
-    Binary,
-}
-
-impl Default for ColumnType {
-    fn default() -> Self {
-        ColumnType::VarChar
-    }
+pub enum ColumnDefault {
+    Value(DataEnum),
+    CurrentTimestamp,
+    CurrentDate,
+    CurrentTime,
 }
 
-/// a column mainly contains four arguments
+/// a column mainly contains four arguments, with optional default value,
+/// auto increment (identity) and description
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Column {
     pub name: String,
     pub col_type: ColumnType,
     pub null: Option<bool>,
     pub key: Option<ColumnKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<ColumnDefault>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_increment: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+/// named unique constraint on one or more columns
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UniqueKey {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+/// named check constraint, the condition is written as a filter
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub condition: Vec<Expression>,
 }
 
-/// table with its' name, columns and optional foreign key
+/// table with its' name, columns, foreign keys and table constraints.
+/// `primary_key` is a composite primary key, which takes precedence over
+/// `ColumnKey::Primary` of columns.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Table {
     pub name: String,
     pub columns: Vec<Column>,
-    pub foreign_key: Option<ForeignKey>,
+    #[serde(default, alias = "foreign_key", deserialize_with = "one_or_many")]
+    pub foreign_keys: Vec<ForeignKey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_key: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_keys: Option<Vec<UniqueKey>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checks: Option<Vec<Check>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl Table {
+    /// primary key columns, from `primary_key` or else from column keys
+    pub fn primary_key_columns(&self) -> Vec<&str> {
+        match &self.primary_key {
+            Some(pk) if !pk.is_empty() => pk.iter().map(|c| c.as_str()).collect(),
+            _ => self
+                .columns
+                .iter()
+                .filter(|c| c.key == Some(ColumnKey::Primary))
+                .map(|c| c.name.as_str())
+                .collect(),
+        }
+    }
 }
 
 /// order type
@@ -78,29 +113,25 @@ pub struct Index {
     pub columns: Vec<Order>,
 }
 
-/// foreign key direction
+/// foreign key direction, can refer to multiple columns
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ForeignKeyDir {
     pub table: String,
-    pub column: String,
+    #[serde(alias = "column", deserialize_with = "one_or_many")]
+    pub columns: Vec<String>,
 }
 
 /// foreign key action
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub enum ForeignKeyAction {
     Restrict,
     Cascade,
     SetNull,
+    #[default]
     NoAction,
     SetDefault,
 }
 
-impl Default for ForeignKeyAction {
-    fn default() -> Self {
-        ForeignKeyAction::NoAction
-    }
-}
-
 /// foreign key with its' unique name, from & to table relations, and actions
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct ForeignKey {
@@ -111,73 +142,261 @@ pub struct ForeignKey {
     pub on_update: ForeignKeyAction,
 }
 
-/// schema indicates a database's tables (not in use)
+/// view defined by a select, with optional column names. A materialized view
+/// stores its rows, which Postgres only supports.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct View {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    pub select: Select,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub materialized: Option<bool>,
+}
+
+/// schema indicates a database's tables, indices and views
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Schema {
     pub schema: String,
     pub tables: Vec<Table>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub indexes: Option<Vec<Index>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub views: Option<Vec<View>>,
 }
 
-/// general data type
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Deserialize)]
 #[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// accept `null`, a single value or a list of values, for the fields that
+/// used to hold a single value
+fn one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let v = Option::<OneOrMany<T>>::deserialize(deserializer)?;
+    Ok(match v {
+        None => vec![],
+        Some(OneOrMany::One(t)) => vec![t],
+        Some(OneOrMany::Many(v)) => v,
+    })
+}
+
+/// general data type. Integers, floats, strings, booleans and `null` are plain
+/// JSON values, the others are tagged with their variant so that they never
+/// read back as strings, e.g. `{"Date": "2021-06-01"}`, `{"Decimal": "12.50"}`,
+/// `{"Bytes": "<base64>"}` or `{"Json": {"a": 1}}`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "DataRepr", into = "DataRepr")]
 pub enum DataEnum {
     Integer(i64),
     Float(f64),
     String(String),
     Bool(bool),
     Null,
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    Time(NaiveTime),
+    Decimal(Decimal),
+    Bytes(Vec<u8>),
+    Uuid(Uuid),
+    Json(serde_json::Value),
 }
 
-impl From<i8> for DataEnum {
-    fn from(v: i8) -> Self {
-        DataEnum::Integer(v as i64)
-    }
+/// serde form of `DataEnum`
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DataRepr {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Bool(bool),
+    Null,
+    Typed(TypedRepr),
+}
+
+#[derive(Serialize, Deserialize)]
+enum TypedRepr {
+    Date(String),
+    DateTime(String),
+    Time(String),
+    Decimal(String),
+    Bytes(String),
+    Uuid(String),
+    Json(serde_json::Value),
 }
 
-impl From<i16> for DataEnum {
-    fn from(v: i16) -> Self {
-        DataEnum::Integer(v as i64)
+/// `YYYY-MM-DD HH:MM:SS` with optional fractional seconds, a `T` separator is also accepted
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.f";
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+
+impl DataEnum {
+    /// text of a date, time, decimal or UUID as written in SQL, e.g. `2021-06-01 08:30:00`
+    pub(crate) fn typed_text(&self) -> Option<String> {
+        match self {
+            DataEnum::Date(v) => Some(v.to_string()),
+            DataEnum::DateTime(v) => Some(v.format(DATE_TIME_FORMAT).to_string()),
+            DataEnum::Time(v) => Some(v.format(TIME_FORMAT).to_string()),
+            DataEnum::Decimal(v) => Some(v.to_string()),
+            DataEnum::Uuid(v) => Some(v.to_hyphenated().to_string()),
+            _ => None,
+        }
     }
 }
 
-impl From<i32> for DataEnum {
-    fn from(v: i32) -> Self {
-        DataEnum::Integer(v as i64)
+impl From<DataEnum> for DataRepr {
+    fn from(v: DataEnum) -> Self {
+        let text = v.typed_text();
+        match v {
+            DataEnum::Integer(v) => DataRepr::Integer(v),
+            DataEnum::Float(v) => DataRepr::Float(v),
+            DataEnum::String(v) => DataRepr::String(v),
+            DataEnum::Bool(v) => DataRepr::Bool(v),
+            DataEnum::Null => DataRepr::Null,
+            DataEnum::Date(_) => DataRepr::Typed(TypedRepr::Date(text.unwrap())),
+            DataEnum::DateTime(_) => DataRepr::Typed(TypedRepr::DateTime(text.unwrap())),
+            DataEnum::Time(_) => DataRepr::Typed(TypedRepr::Time(text.unwrap())),
+            DataEnum::Decimal(_) => DataRepr::Typed(TypedRepr::Decimal(text.unwrap())),
+            DataEnum::Uuid(_) => DataRepr::Typed(TypedRepr::Uuid(text.unwrap())),
+            DataEnum::Bytes(v) => DataRepr::Typed(TypedRepr::Bytes(base64::encode(v))),
+            DataEnum::Json(v) => DataRepr::Typed(TypedRepr::Json(v)),
+        }
     }
 }
 
-impl From<f32> for DataEnum {
-    fn from(v: f32) -> Self {
-        DataEnum::Float(v as f64)
+impl TryFrom<DataRepr> for DataEnum {
+    type Error = String;
+
+    fn try_from(v: DataRepr) -> Result<Self, Self::Error> {
+        let typed = match v {
+            DataRepr::Integer(v) => return Ok(DataEnum::Integer(v)),
+            DataRepr::Float(v) => return Ok(DataEnum::Float(v)),
+            DataRepr::String(v) => return Ok(DataEnum::String(v)),
+            DataRepr::Bool(v) => return Ok(DataEnum::Bool(v)),
+            DataRepr::Null => return Ok(DataEnum::Null),
+            DataRepr::Typed(t) => t,
+        };
+        let invalid = |kind: &str, s: &str| format!("invalid {} {:?}", kind, s);
+        match typed {
+            TypedRepr::Date(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                .map(DataEnum::Date)
+                .map_err(|_| invalid("date", &s)),
+            TypedRepr::DateTime(s) => NaiveDateTime::parse_from_str(&s, DATE_TIME_FORMAT)
+                .or_else(|_| NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S%.f"))
+                .map(DataEnum::DateTime)
+                .map_err(|_| invalid("date time", &s)),
+            TypedRepr::Time(s) => NaiveTime::parse_from_str(&s, TIME_FORMAT)
+                .map(DataEnum::Time)
+                .map_err(|_| invalid("time", &s)),
+            TypedRepr::Decimal(s) => s
+                .parse()
+                .map(DataEnum::Decimal)
+                .map_err(|_| invalid("decimal", &s)),
+            TypedRepr::Bytes(s) => base64::decode(&s)
+                .map(DataEnum::Bytes)
+                .map_err(|_| invalid("base64", &s)),
+            TypedRepr::Uuid(s) => Uuid::parse_str(&s)
+                .map(DataEnum::Uuid)
+                .map_err(|_| invalid("uuid", &s)),
+            TypedRepr::Json(v) => Ok(DataEnum::Json(v)),
+        }
     }
 }
 
-impl From<String> for DataEnum {
-    fn from(v: String) -> Self {
-        DataEnum::String(v)
+macro_rules! impl_from {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$t> for DataEnum {
+                fn from(v: $t) -> Self {
+                    DataEnum::$variant(v.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    i8 => Integer,
+    i16 => Integer,
+    i32 => Integer,
+    i64 => Integer,
+    u8 => Integer,
+    u16 => Integer,
+    u32 => Integer,
+    f32 => Float,
+    f64 => Float,
+    bool => Bool,
+    String => String,
+    &str => String,
+    NaiveDate => Date,
+    NaiveDateTime => DateTime,
+    NaiveTime => Time,
+    Decimal => Decimal,
+    Vec<u8> => Bytes,
+    &[u8] => Bytes,
+    Uuid => Uuid,
+    serde_json::Value => Json,
+);
+
+/// stored as the UTC date time
+impl<Tz: TimeZone> From<DateTime<Tz>> for DataEnum {
+    fn from(v: DateTime<Tz>) -> Self {
+        DataEnum::DateTime(v.naive_utc())
     }
 }
 
-impl From<&str> for DataEnum {
-    fn from(v: &str) -> Self {
-        DataEnum::String(v.to_owned())
+/// `None` is `Null`
+impl<T: Into<DataEnum>> From<Option<T>> for DataEnum {
+    fn from(v: Option<T>) -> Self {
+        v.map_or(DataEnum::Null, Into::into)
     }
 }
 
-/// column name, can be alias. used it in `select`
+/// aggregate function
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AggregateFunction {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// aggregate function applied on a column (`*` for all rows) with an optional alias
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    pub column: String,
+    pub alias: Option<String>,
+}
+
+/// column name, can be alias, aggregate, computed expression or window
+/// function. used it in `select`
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum ColumnAlias {
     Simple(String),
     Alias((String, String)),
+    Aggregate(Aggregate),
+    Computed(Computed),
+    Window(Window),
 }
 
 impl ColumnAlias {
+    /// column name, or the alias of a computed or window column
     pub fn name(&self) -> String {
         match self {
             ColumnAlias::Simple(s) => s.to_owned(),
             ColumnAlias::Alias((s, _)) => s.to_owned(),
+            ColumnAlias::Aggregate(a) => a.column.to_owned(),
+            ColumnAlias::Computed(c) => c.alias.clone().unwrap_or_default(),
+            ColumnAlias::Window(w) => w.alias.clone().unwrap_or_default(),
         }
     }
 }
@@ -211,4 +430,78 @@ mod tests_common {
 
         assert_eq!(deserialized, table);
     }
+
+    #[test]
+    fn convert_legacy_foreign_key() {
+        let json = r#"
+        {
+            "name": "test",
+            "columns": [{"name": "pid", "col_type": "Int", "null": null, "key": null}],
+            "foreign_key": {
+                "name": "fk",
+                "from": {"table": "test", "column": "pid"},
+                "to": {"table": "parent", "column": "id"},
+                "on_delete": "Cascade",
+                "on_update": "NoAction"
+            }
+        }"#;
+
+        let deserialized: Table = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.foreign_keys.len(), 1);
+        assert_eq!(deserialized.foreign_keys[0].from.columns, vec!["pid"]);
+
+        let json = r#"{"name": "test", "columns": [], "foreign_key": null}"#;
+
+        let deserialized: Table = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.foreign_keys, vec![]);
+    }
+
+    #[test]
+    fn typed_data() {
+        let json = r#"
+        [
+            1, 1.5, "2021-06-01", true, null,
+            {"Date": "2021-06-01"},
+            {"DateTime": "2021-06-01T08:30:00.5"},
+            {"Time": "08:30:00"},
+            {"Decimal": "12.50"},
+            {"Bytes": "AQL/"},
+            {"Uuid": "67e55044-10b1-426f-9247-bb680e5fe0c8"},
+            {"Json": {"a": [1]}}
+        ]"#;
+
+        let deserialized: Vec<DataEnum> = serde_json::from_str(json).unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2021, 6, 1).unwrap();
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let expected = vec![
+            DataEnum::from(1i64),
+            1.5f64.into(),
+            "2021-06-01".into(),
+            true.into(),
+            Option::<i32>::None.into(),
+            date.into(),
+            date.and_hms_milli_opt(8, 30, 0, 500).unwrap().into(),
+            NaiveTime::from_hms_opt(8, 30, 0).unwrap().into(),
+            "12.50".parse::<Decimal>().unwrap().into(),
+            vec![1u8, 2, 255].into(),
+            Some(uuid).into(),
+            serde_json::json!({"a": [1]}).into(),
+        ];
+        assert_eq!(deserialized, expected);
+
+        let serialized = serde_json::to_string(&expected[5..8]).unwrap();
+        assert_eq!(
+            serialized,
+            r#"[{"Date":"2021-06-01"},{"DateTime":"2021-06-01 08:30:00.500"},{"Time":"08:30:00"}]"#
+        );
+        let serialized = serde_json::to_string(&expected).unwrap();
+        let deserialized: Vec<DataEnum> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, expected);
+
+        assert!(serde_json::from_str::<DataEnum>(r#"{"Date": "2021-13-01"}"#).is_err());
+        assert!(serde_json::from_str::<DataEnum>(r#"{"Other": "x"}"#).is_err());
+    }
 }
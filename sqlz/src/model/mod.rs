@@ -1,7 +1,9 @@
+pub mod column_type;
 pub mod common;
 pub mod query;
 pub mod schema;
 
+pub use column_type::*;
 pub use common::*;
 
 pub use schema::foreign_key_create_drop::*;
@@ -12,7 +14,9 @@ pub use schema::table_drop::*;
 pub use schema::table_list::*;
 pub use schema::table_rename::*;
 pub use schema::table_truncate::*;
+pub use schema::view_create_drop::*;
 
+pub use query::computed::*;
 pub use query::delete::*;
 pub use query::insert::*;
 pub use query::select::*;
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{AggregateFunction, ColumnType, DataEnum, Expression, Order};
+
+/// expression of a computed column, e.g. `{"Arithmetic": [{"Column": "price"}, "Mul", {"Column": "qty"}]}`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum ColumnExpr {
+    Column(String),
+    Value(DataEnum),
+    Arithmetic(Box<(ColumnExpr, ArithmeticOperator, ColumnExpr)>),
+    Case(Case),
+    Coalesce(Vec<ColumnExpr>),
+    Cast(Box<(ColumnExpr, ColumnType)>),
+    Function(Function),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+/// `CASE WHEN ... THEN ... ELSE ... END`, conditions are filters
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Case {
+    pub when: Vec<When>,
+    #[serde(rename = "else", skip_serializing_if = "Option::is_none")]
+    pub otherwise: Option<Box<ColumnExpr>>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct When {
+    pub condition: Vec<Expression>,
+    pub then: ColumnExpr,
+}
+
+/// string and date functions, written in each dialect's own way
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum Function {
+    Upper(Box<ColumnExpr>),
+    Lower(Box<ColumnExpr>),
+    Trim(Box<ColumnExpr>),
+    /// length in characters
+    Length(Box<ColumnExpr>),
+    /// `null` if any part is `null`
+    Concat(Vec<ColumnExpr>),
+    /// 1-based start, and an optional length
+    Substring(Box<(ColumnExpr, u64, Option<u64>)>),
+    CurrentTimestamp,
+    /// part of a date or time as an integer
+    Extract(Box<(DatePart, ColumnExpr)>),
+    /// date or time truncated to the start of a part, e.g. the first day of the month
+    DateTrunc(Box<(DatePart, ColumnExpr)>),
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum DatePart {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+/// computed column with an optional alias
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Computed {
+    pub expr: ColumnExpr,
+    pub alias: Option<String>,
+}
+
+/// window function column. Aggregates with `order` are running totals, as
+/// the default frame ends at the current row.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Window {
+    pub window: WindowFunction,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub partition_by: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub order: Vec<Order>,
+    pub alias: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum WindowFunction {
+    RowNumber,
+    Rank,
+    DenseRank,
+    Lag(Shift),
+    Lead(Shift),
+    Aggregate((AggregateFunction, String)),
+}
+
+/// value of a column in a previous or following row, `offset` is 1 by default
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Shift {
+    pub column: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<DataEnum>,
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::filter::validate_filter;
+use crate::{Expression, SqlzError, SqlzResult};
+
+/// a delete without filter is refused, unless `allow_unfiltered` is set.
+/// `returning` is not available in MySql.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Delete {
+    pub table: String,
+    pub filter: Option<Vec<Expression>>,
+    pub limit: Option<u64>,
+    pub returning: Option<Vec<String>>,
+    pub allow_unfiltered: Option<bool>,
+}
+
+impl Delete {
+    /// check the delete is safe to be executed
+    pub fn check(&self) -> SqlzResult<()> {
+        validate_filter(self.filter.as_deref().unwrap_or_default(), "/filter")?;
+        let filtered = matches!(&self.filter, Some(f) if !f.is_empty());
+        if !filtered && self.allow_unfiltered != Some(true) {
+            return Err(SqlzError::Unfiltered(self.table.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_delete {
+    use super::*;
+    use crate::{Condition, DataEnum, Equation};
+
+    #[test]
+    fn deletion() {
+        let deletion = Delete {
+            table: "sqlz".to_owned(),
+            filter: Some(vec![Expression::Simple(Condition {
+                column: "c1".to_owned(),
+                equation: Equation::Less(DataEnum::Integer(23)),
+            })]),
+            limit: Some(10),
+            returning: Some(vec!["c1".to_owned()]),
+            allow_unfiltered: None,
+        };
+
+        let cvt = serde_json::to_string(&deletion).unwrap();
+
+        let res = "{\"table\":\"sqlz\",\"filter\":[{\"column\":\"c1\",\"equation\":{\"Less\":23}}],\"limit\":10,\"returning\":[\"c1\"],\"allow_unfiltered\":null}";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Delete = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, deletion);
+        assert_eq!(deserialized.check(), Ok(()));
+    }
+
+    #[test]
+    fn unfiltered() {
+        let json = r#"{"table":"sqlz"}"#;
+        let mut deletion: Delete = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            deletion.check(),
+            Err(SqlzError::Unfiltered("sqlz".to_owned()))
+        );
+
+        deletion.allow_unfiltered = Some(true);
+
+        assert_eq!(deletion.check(), Ok(()));
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{DataEnum, Select};
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Insert {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub values: InsertValues,
+}
+
+/// values to be inserted, either rows of data or the result of a `Select`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum InsertValues {
+    Rows(Vec<Vec<DataEnum>>),
+    Select(Box<Select>),
+}
+
+#[cfg(test)]
+mod tests_insert {
+    use super::*;
+    use crate::{ColumnAlias, Condition, Equation, Expression};
+
+    #[test]
+    fn insertion() {
+        let insertion = Insert {
+            table: "sqlz".to_owned(),
+            columns: vec!["c1".to_owned(), "c2".to_owned()],
+            values: InsertValues::Rows(vec![
+                vec![DataEnum::Integer(1), DataEnum::from("T1")],
+                vec![DataEnum::Integer(2), DataEnum::Null],
+            ]),
+        };
+
+        let cvt = serde_json::to_string(&insertion).unwrap();
+
+        let res = "{\"table\":\"sqlz\",\"columns\":[\"c1\",\"c2\"],\"values\":{\"Rows\":[[1,\"T1\"],[2,null]]}}";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Insert = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, insertion);
+    }
+
+    #[test]
+    fn insertion_from_select() {
+        let insertion = Insert {
+            table: "sqlz".to_owned(),
+            columns: vec!["c1".to_owned()],
+            values: InsertValues::Select(Box::new(Select {
+                with: None,
+                table: "sqlz_src".into(),
+                alias: None,
+                columns: vec![ColumnAlias::Simple("c1".to_owned())],
+                join: None,
+                filter: Some(vec![Expression::Simple(Condition {
+                    column: "c1".to_owned(),
+                    equation: Equation::Greater(DataEnum::Integer(23)),
+                })]),
+                group_by: None,
+                having: None,
+                order: None,
+                limit: None,
+                offset: None,
+                set_operations: None,
+                cursor: None,
+            })),
+        };
+
+        let cvt = serde_json::to_string(&insertion).unwrap();
+
+        let res = "{\"table\":\"sqlz\",\"columns\":[\"c1\"],\"values\":{\"Select\":{\"table\":\"sqlz_src\",\"columns\":[\"c1\"],\"filter\":[{\"column\":\"c1\",\"equation\":{\"Greater\":23}}],\"order\":null,\"limit\":null,\"offset\":null}}}";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Insert = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, insertion);
+    }
+}
@@ -1,17 +1,188 @@
-use serde::{Deserialize, Serialize};
+use std::fmt;
 
-use crate::{ColumnAlias, DataEnum, Order};
+use serde::{Deserialize, Serialize};
 
-// TODO: Join & GroupBy
+use crate::filter::filter_errors;
+use crate::{ColumnAlias, DataEnum, FilterError, Order, SqlzError, SqlzResult};
 
+/// columns can be qualified by table name or alias, e.g. `t1.c1`.
+/// `having` conditions refer to aggregate columns by their aliases.
+/// `order`, `limit` and `offset` apply to the result of `set_operations`.
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct Select {
-    pub table: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub with: Option<Vec<Cte>>,
+    pub table: TableSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
     pub columns: Vec<ColumnAlias>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub join: Option<Vec<Join>>,
     pub filter: Option<Vec<Expression>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group_by: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub having: Option<Vec<Expression>>,
     pub order: Option<Vec<Order>>,
     pub limit: Option<u64>,
     pub offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_operations: Option<Vec<SetOperation>>,
+    /// keyset pagination, rows after the one encoded by the cursor in `order`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+impl Select {
+    /// check `filter`, `having` and join conditions are well formed, also
+    /// those of nested selects
+    pub fn check(&self) -> SqlzResult<()> {
+        let mut errors = vec![];
+        self.collect_errors("", &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SqlzError::InvalidFilter(errors))
+        }
+    }
+
+    fn collect_errors(&self, path: &str, errors: &mut Vec<FilterError>) {
+        for (i, cte) in self.with.iter().flatten().enumerate() {
+            cte.select
+                .collect_errors(&format!("{}/with/{}/select", path, i), errors);
+        }
+        if let TableSource::Derived(d) = &self.table {
+            d.select
+                .collect_errors(&format!("{}/table/select", path), errors);
+        }
+        for (i, join) in self.join.iter().flatten().enumerate() {
+            if let TableSource::Derived(d) = &join.table {
+                d.select
+                    .collect_errors(&format!("{}/join/{}/table/select", path, i), errors);
+            }
+            if let Some(on) = &join.on {
+                expression_errors(on, &format!("{}/join/{}/on", path, i), errors);
+            }
+        }
+        if let Some(filter) = &self.filter {
+            expression_errors(filter, &format!("{}/filter", path), errors);
+        }
+        if let Some(having) = &self.having {
+            expression_errors(having, &format!("{}/having", path), errors);
+        }
+        for (i, op) in self.set_operations.iter().flatten().enumerate() {
+            let (name, select) = op.parts();
+            select.collect_errors(&format!("{}/set_operations/{}/{}", path, i, name), errors);
+        }
+    }
+}
+
+/// errors of a filter, and of selects nested in it
+fn expression_errors(exprs: &[Expression], path: &str, errors: &mut Vec<FilterError>) {
+    errors.extend(filter_errors(exprs, path));
+    subquery_errors(exprs, path, errors);
+}
+
+fn subquery_errors(exprs: &[Expression], path: &str, errors: &mut Vec<FilterError>) {
+    for (i, e) in exprs.iter().enumerate() {
+        let p = format!("{}/{}", path, i);
+        match e {
+            Expression::Simple(Condition {
+                equation: Equation::InSelect(s),
+                ..
+            }) => s.collect_errors(&format!("{}/equation/InSelect", p), errors),
+            Expression::Simple(Condition {
+                equation: Equation::NotInSelect(s),
+                ..
+            }) => s.collect_errors(&format!("{}/equation/NotInSelect", p), errors),
+            Expression::Nest(v) => subquery_errors(v, &p, errors),
+            Expression::Not(n) => subquery_errors(&n.not, &format!("{}/not", p), errors),
+            Expression::Exists(e) => e.exists.collect_errors(&format!("{}/exists", p), errors),
+            _ => {}
+        }
+    }
+}
+
+/// a table name, or a derived table `{"select": {...}}` which needs an alias
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum TableSource {
+    Table(String),
+    Derived(Subquery),
+}
+
+impl From<&str> for TableSource {
+    fn from(v: &str) -> Self {
+        TableSource::Table(v.to_owned())
+    }
+}
+
+impl From<String> for TableSource {
+    fn from(v: String) -> Self {
+        TableSource::Table(v)
+    }
+}
+
+impl fmt::Display for TableSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableSource::Table(t) => write!(f, "{}", t),
+            TableSource::Derived(_) => write!(f, "(subquery)"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Subquery {
+    pub select: Box<Select>,
+}
+
+/// common table expression `name (columns) AS (select)` of a `WITH` clause
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Cte {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    pub select: Select,
+}
+
+/// combines the result with another select, which has no `order`, `limit`,
+/// `offset` or set operations of its own
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum SetOperation {
+    Union(Select),
+    UnionAll(Select),
+    Intersect(Select),
+    Except(Select),
+}
+
+impl SetOperation {
+    pub(crate) fn parts(&self) -> (&'static str, &Select) {
+        match self {
+            SetOperation::Union(s) => ("Union", s),
+            SetOperation::UnionAll(s) => ("UnionAll", s),
+            SetOperation::Intersect(s) => ("Intersect", s),
+            SetOperation::Except(s) => ("Except", s),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum JoinType {
+    Inner,
+    Left,
+    Right,
+    Full,
+    Cross,
+}
+
+/// join a table with an optional alias. `on` is required except cross join
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Join {
+    pub join_type: JoinType,
+    pub table: TableSource,
+    pub alias: Option<String>,
+    pub on: Option<Vec<Expression>>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -20,12 +191,31 @@ pub struct SelectResult(pub serde_json::value::Value);
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct SelectVecResult(pub Vec<SelectResult>);
 
+/// a page of keyset pagination, `next_cursor` is none on the last page
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SelectPage {
+    pub rows: SelectVecResult,
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum Conjunction {
     AND,
     OR,
 }
 
+/// comparison between two columns
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+}
+
+/// `Equal(Null)` and `NotEqual(Null)` are the same as `IsNull` and `IsNotNull`
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum Equation {
     Equal(DataEnum),
@@ -37,6 +227,18 @@ pub enum Equation {
     In(Vec<DataEnum>),
     Between((DataEnum, DataEnum)),
     Like(String),
+    Column((Comparison, String)),
+    IsNull,
+    IsNotNull,
+    NotIn(Vec<DataEnum>),
+    NotBetween((DataEnum, DataEnum)),
+    NotLike(String),
+    ILike(String),
+    NotILike(String),
+    Regex(String),
+    NotRegex(String),
+    InSelect(Box<Select>),
+    NotInSelect(Box<Select>),
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
@@ -45,17 +247,32 @@ pub struct Condition {
     pub equation: Equation,
 }
 
+/// `EXISTS` of a select, `{"exists": {...}}` in JSON
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Existence {
+    pub exists: Box<Select>,
+}
+
+/// negation of nested expressions, `{"not": [...]}` in JSON
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Negation {
+    pub not: Vec<Expression>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
 pub enum Expression {
     Conjunction(Conjunction),
     Simple(Condition),
     Nest(Vec<Expression>),
+    Not(Negation),
+    Exists(Existence),
 }
 
 #[cfg(test)]
 mod tests_select {
     use super::*;
+    use crate::{Aggregate, AggregateFunction};
 
     #[test]
     fn selection() {
@@ -83,15 +300,22 @@ mod tests_select {
             ]),
         ];
         let selection = Select {
-            table: "sqlz".to_owned(),
+            with: None,
+            table: "sqlz".into(),
+            alias: None,
             columns: vec![
                 ColumnAlias::Simple("c1".to_owned()),
                 ColumnAlias::Alias(("c2".to_owned(), "c2_t".to_owned())),
             ],
+            join: None,
             filter: Some(conditions),
+            group_by: None,
+            having: None,
             order: None,
             limit: Some(10),
             offset: Some(20),
+            set_operations: None,
+            cursor: None,
         };
 
         let cvt = serde_json::to_string(&selection).unwrap();
@@ -119,4 +343,110 @@ mod tests_select {
 
         assert_eq!(cvt, res);
     }
+
+    #[test]
+    fn selection_join() {
+        let selection = Select {
+            with: None,
+            table: "sqlz".into(),
+            alias: Some("a".to_owned()),
+            columns: vec![
+                ColumnAlias::Simple("a.c1".to_owned()),
+                ColumnAlias::Alias(("b.c2".to_owned(), "c2_t".to_owned())),
+            ],
+            join: Some(vec![Join {
+                join_type: JoinType::Left,
+                table: "sqlz_b".into(),
+                alias: Some("b".to_owned()),
+                on: Some(vec![Expression::Simple(Condition {
+                    column: "a.id".to_owned(),
+                    equation: Equation::Column((Comparison::Equal, "b.id".to_owned())),
+                })]),
+            }]),
+            filter: None,
+            group_by: None,
+            having: None,
+            order: None,
+            limit: None,
+            offset: None,
+            set_operations: None,
+            cursor: None,
+        };
+
+        let cvt = serde_json::to_string(&selection).unwrap();
+
+        let res = "{\"table\":\"sqlz\",\"alias\":\"a\",\"columns\":[\"a.c1\",[\"b.c2\",\"c2_t\"]],\"join\":[{\"join_type\":\"Left\",\"table\":\"sqlz_b\",\"alias\":\"b\",\"on\":[{\"column\":\"a.id\",\"equation\":{\"Column\":[\"Equal\",\"b.id\"]}}]}],\"filter\":null,\"order\":null,\"limit\":null,\"offset\":null}";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Select = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, selection);
+    }
+
+    #[test]
+    fn selection_group_by() {
+        let selection = Select {
+            with: None,
+            table: "sqlz".into(),
+            alias: None,
+            columns: vec![
+                ColumnAlias::Simple("c1".to_owned()),
+                ColumnAlias::Aggregate(Aggregate {
+                    function: AggregateFunction::Count,
+                    column: "*".to_owned(),
+                    alias: Some("cnt".to_owned()),
+                }),
+            ],
+            join: None,
+            filter: None,
+            group_by: Some(vec!["c1".to_owned()]),
+            having: Some(vec![Expression::Simple(Condition {
+                column: "cnt".to_owned(),
+                equation: Equation::Greater(DataEnum::Integer(1)),
+            })]),
+            order: None,
+            limit: None,
+            offset: None,
+            set_operations: None,
+            cursor: None,
+        };
+
+        let cvt = serde_json::to_string(&selection).unwrap();
+
+        let res = "{\"table\":\"sqlz\",\"columns\":[\"c1\",{\"function\":\"Count\",\"column\":\"*\",\"alias\":\"cnt\"}],\"filter\":null,\"group_by\":[\"c1\"],\"having\":[{\"column\":\"cnt\",\"equation\":{\"Greater\":1}}],\"order\":null,\"limit\":null,\"offset\":null}";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Select = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, selection);
+    }
+
+    #[test]
+    fn selection_null_not() {
+        let conditions = vec![
+            Expression::Simple(Condition {
+                column: "c1".to_owned(),
+                equation: Equation::IsNull,
+            }),
+            Expression::Conjunction(Conjunction::AND),
+            Expression::Not(Negation {
+                not: vec![Expression::Simple(Condition {
+                    column: "c2".to_owned(),
+                    equation: Equation::ILike("%a%".to_owned()),
+                })],
+            }),
+        ];
+
+        let cvt = serde_json::to_string(&conditions).unwrap();
+
+        let res = "[{\"column\":\"c1\",\"equation\":\"IsNull\"},\"AND\",{\"not\":[{\"column\":\"c2\",\"equation\":{\"ILike\":\"%a%\"}}]}]";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Vec<Expression> = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, conditions);
+    }
 }
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::filter::validate_filter;
+use crate::{DataEnum, Expression, SqlzError, SqlzResult};
+
+/// reference to another column, e.g. `SET c1 = c2`
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ColumnRef {
+    pub column: String,
+}
+
+/// value assigned to a column, either a literal or another column
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum UpdateValue {
+    Value(DataEnum),
+    Column(ColumnRef),
+}
+
+/// an update without filter is refused, unless `allow_unfiltered` is set
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Update {
+    pub table: String,
+    pub set: BTreeMap<String, UpdateValue>,
+    pub filter: Option<Vec<Expression>>,
+    pub allow_unfiltered: Option<bool>,
+}
+
+impl Update {
+    /// check the update is safe to be executed
+    pub fn check(&self) -> SqlzResult<()> {
+        if self.set.is_empty() {
+            return Err(SqlzError::InvalidValue("update without columns".to_owned()));
+        }
+        validate_filter(self.filter.as_deref().unwrap_or_default(), "/filter")?;
+        let filtered = matches!(&self.filter, Some(f) if !f.is_empty());
+        if !filtered && self.allow_unfiltered != Some(true) {
+            return Err(SqlzError::Unfiltered(self.table.clone()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests_update {
+    use super::*;
+    use crate::{Condition, Equation};
+
+    #[test]
+    fn updating() {
+        let mut set = BTreeMap::new();
+        set.insert("c1".to_owned(), UpdateValue::Value(DataEnum::from("T1")));
+        set.insert(
+            "c2".to_owned(),
+            UpdateValue::Column(ColumnRef {
+                column: "c3".to_owned(),
+            }),
+        );
+        let updating = Update {
+            table: "sqlz".to_owned(),
+            set,
+            filter: Some(vec![Expression::Simple(Condition {
+                column: "c4".to_owned(),
+                equation: Equation::Equal(DataEnum::Integer(1)),
+            })]),
+            allow_unfiltered: None,
+        };
+
+        let cvt = serde_json::to_string(&updating).unwrap();
+
+        let res = "{\"table\":\"sqlz\",\"set\":{\"c1\":\"T1\",\"c2\":{\"column\":\"c3\"}},\"filter\":[{\"column\":\"c4\",\"equation\":{\"Equal\":1}}],\"allow_unfiltered\":null}";
+
+        assert_eq!(cvt, res);
+
+        let deserialized: Update = serde_json::from_str(res).unwrap();
+
+        assert_eq!(deserialized, updating);
+        assert_eq!(deserialized.check(), Ok(()));
+    }
+
+    #[test]
+    fn unfiltered() {
+        let json = r#"{"table":"sqlz","set":{"c1":null},"filter":[]}"#;
+        let mut updating: Update = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            updating.check(),
+            Err(SqlzError::Unfiltered("sqlz".to_owned()))
+        );
+
+        updating.allow_unfiltered = Some(true);
+
+        assert_eq!(updating.check(), Ok(()));
+    }
+}
@@ -7,3 +7,4 @@ pub mod table_drop;
 pub mod table_list;
 pub mod table_rename;
 pub mod table_truncate;
+pub mod view_create_drop;
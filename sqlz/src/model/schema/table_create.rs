@@ -1,3 +1,15 @@
 use super::super::Table;
+use super::index_create_drop::IndexCreate;
 
 pub type TableCreate = Table;
+
+/// a type defining a table, can be derived by `#[derive(SqlzTable)]` with the
+/// `derive` feature
+pub trait SqlzTable {
+    fn table_create() -> TableCreate;
+
+    /// indexes on more than one column, single column indexes are `ColumnKey::Multiple`
+    fn indexes() -> Vec<IndexCreate> {
+        vec![]
+    }
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use super::super::View;
+
+pub type ViewCreate = View;
+
+/// `materialized` is required to drop a materialized view
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ViewDrop {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub materialized: Option<bool>,
+}
+
+/// reload the rows of a materialized view, `concurrently` keeps it readable
+/// meanwhile but requires a unique index on it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ViewRefresh {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrently: Option<bool>,
+}
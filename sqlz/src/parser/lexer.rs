@@ -0,0 +1,129 @@
+use super::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    /// unquoted identifier or keyword
+    Word(String),
+    /// identifier quoted by `"` or `` ` ``
+    Quoted(String),
+    Str(String),
+    Number(String),
+    Symbol(&'static str),
+    Eof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Spanned {
+    pub(crate) token: Token,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// longer symbols come first
+pub(crate) const SYMBOLS: &[&str] = &[
+    "<>", "!=", "<=", ">=", "!~", "::", "(", ")", ",", ".", ";", "*", "=", "<", ">", "~", "-", "+",
+    "[", "]", "?", "$", "/", "%", "|", "&", "@", "#", "^", "{", "}",
+];
+
+/// split sql into tokens, comments are skipped
+pub(crate) fn tokenize(sql: &str) -> Result<Vec<Spanned>, ParseError> {
+    let bytes = sql.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = sql[i..].chars().next().unwrap();
+        let start = i;
+
+        if c.is_whitespace() {
+            i += c.len_utf8();
+            continue;
+        }
+        if sql[i..].starts_with("--") {
+            i = sql[i..].find('\n').map_or(sql.len(), |n| i + n);
+            continue;
+        }
+        if sql[i..].starts_with("/*") {
+            i = sql[i + 2..]
+                .find("*/")
+                .map(|n| i + 2 + n + 2)
+                .ok_or_else(|| ParseError::new("unterminated comment", start, sql.len()))?;
+            continue;
+        }
+
+        let token = if c == '\'' || c == '"' || c == '`' {
+            let (s, end) = quoted(sql, i, c)?;
+            i = end;
+            if c == '\'' {
+                Token::Str(s)
+            } else {
+                Token::Quoted(s)
+            }
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                i += 1;
+            }
+            if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+                let mut j = i + 1;
+                if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+                    j += 1;
+                }
+                if j < bytes.len() && bytes[j].is_ascii_digit() {
+                    i = j;
+                    while i < bytes.len() && bytes[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            Token::Number(sql[start..i].to_owned())
+        } else if c.is_alphabetic() || c == '_' {
+            let len = sql[i..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(sql.len() - i);
+            i += len;
+            Token::Word(sql[start..i].to_owned())
+        } else if let Some(s) = SYMBOLS.iter().find(|s| sql[i..].starts_with(**s)) {
+            i += s.len();
+            Token::Symbol(s)
+        } else {
+            return Err(ParseError::new(
+                format!("unexpected character '{}'", c),
+                start,
+                start + c.len_utf8(),
+            ));
+        };
+
+        tokens.push(Spanned {
+            token,
+            start,
+            end: i,
+        });
+    }
+
+    tokens.push(Spanned {
+        token: Token::Eof,
+        start: sql.len(),
+        end: sql.len(),
+    });
+    Ok(tokens)
+}
+
+/// read a quoted string starting at `start`, a doubled quote stands for itself
+fn quoted(sql: &str, start: usize, quote: char) -> Result<(String, usize), ParseError> {
+    let mut s = String::new();
+    let mut chars = sql[start + 1..].char_indices().peekable();
+    while let Some((n, c)) = chars.next() {
+        if c == quote {
+            if let Some((_, next)) = chars.peek() {
+                if *next == quote {
+                    s.push(quote);
+                    chars.next();
+                    continue;
+                }
+            }
+            return Ok((s, start + 1 + n + 1));
+        }
+        s.push(c);
+    }
+    Err(ParseError::new("unterminated quote", start, sql.len()))
+}
@@ -0,0 +1,333 @@
+//! Parses a subset of Sql back into models.
+//!
+//! - `SELECT ... FROM ... [JOIN ...] [WHERE ...] [GROUP BY ...] [HAVING ...]
+//!   [ORDER BY ...] [LIMIT ...] [OFFSET ...]`, into `Select`
+//! - `CREATE TABLE ...`, into `TableCreate`
+//!
+//! Anything else is refused by a `ParseError` locating the offending token.
+
+pub(crate) mod lexer;
+pub mod select;
+pub mod table;
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use self::lexer::{tokenize, Spanned, Token};
+use crate::{Select, SqlzError, SqlzResult, TableCreate};
+
+/// parse error with the byte range of the offending text
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ParseError {
+    pub(crate) fn new<S: Into<String>>(message: S, start: usize, end: usize) -> Self {
+        ParseError {
+            message: message.into(),
+            start,
+            end,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.message, self.start, self.end)
+    }
+}
+
+impl From<ParseError> for SqlzError {
+    fn from(e: ParseError) -> Self {
+        SqlzError::Parse(e)
+    }
+}
+
+/// a parsed statement
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Statement {
+    Select(Select),
+    TableCreate(TableCreate),
+}
+
+/// parse a select or a create table statement
+pub fn parse(sql: &str) -> SqlzResult<Statement> {
+    let mut p = Parser::new(sql)?;
+    let statement = if p.is_keyword("SELECT") {
+        Statement::Select(p.select()?)
+    } else if p.is_keyword("CREATE") {
+        Statement::TableCreate(p.table_create()?)
+    } else {
+        return Err(p.unexpected("SELECT or CREATE TABLE"));
+    };
+    p.end()?;
+    Ok(statement)
+}
+
+/// parse a select statement
+pub fn parse_select(sql: &str) -> SqlzResult<Select> {
+    let mut p = Parser::new(sql)?;
+    let select = p.select()?;
+    p.end()?;
+    Ok(select)
+}
+
+/// parse a create table statement
+pub fn parse_table_create(sql: &str) -> SqlzResult<TableCreate> {
+    let mut p = Parser::new(sql)?;
+    let table = p.table_create()?;
+    p.end()?;
+    Ok(table)
+}
+
+/// words which cannot be used as an unquoted identifier or alias
+const RESERVED: &[&str] = &[
+    "SELECT",
+    "FROM",
+    "WHERE",
+    "GROUP",
+    "HAVING",
+    "ORDER",
+    "LIMIT",
+    "OFFSET",
+    "FETCH",
+    "JOIN",
+    "INNER",
+    "LEFT",
+    "RIGHT",
+    "FULL",
+    "CROSS",
+    "NATURAL",
+    "OUTER",
+    "ON",
+    "USING",
+    "AND",
+    "OR",
+    "NOT",
+    "AS",
+    "UNION",
+    "INTERSECT",
+    "EXCEPT",
+    "BY",
+    "IN",
+    "IS",
+    "NULL",
+    "LIKE",
+    "ILIKE",
+    "BETWEEN",
+    "DISTINCT",
+    "WINDOW",
+];
+
+pub(crate) struct Parser<'a> {
+    sql: &'a str,
+    tokens: Vec<Spanned>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(sql: &'a str) -> SqlzResult<Self> {
+        Ok(Parser {
+            sql,
+            tokens: tokenize(sql)?,
+            pos: 0,
+        })
+    }
+
+    pub(crate) fn peek(&self) -> &Spanned {
+        &self.tokens[self.pos]
+    }
+
+    pub(crate) fn peek_nth(&self, n: usize) -> &Spanned {
+        let i = (self.pos + n).min(self.tokens.len() - 1);
+        &self.tokens[i]
+    }
+
+    pub(crate) fn next(&mut self) -> Spanned {
+        let t = self.tokens[self.pos].clone();
+        if self.pos < self.tokens.len() - 1 {
+            self.pos += 1;
+        }
+        t
+    }
+
+    pub(crate) fn is_keyword(&self, keyword: &str) -> bool {
+        is_word(self.peek(), keyword)
+    }
+
+    pub(crate) fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let eaten = self.is_keyword(keyword);
+        if eaten {
+            self.next();
+        }
+        eaten
+    }
+
+    /// eat a sequence of keywords, or nothing if any of them does not match
+    pub(crate) fn eat_keywords(&mut self, keywords: &[&str]) -> bool {
+        let matched = keywords
+            .iter()
+            .enumerate()
+            .all(|(n, k)| is_word(self.peek_nth(n), k));
+        if matched {
+            self.pos += keywords.len();
+        }
+        matched
+    }
+
+    pub(crate) fn expect_keyword(&mut self, keyword: &str) -> SqlzResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    pub(crate) fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(&self.peek().token, Token::Symbol(s) if *s == symbol)
+    }
+
+    pub(crate) fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let eaten = self.is_symbol(symbol);
+        if eaten {
+            self.next();
+        }
+        eaten
+    }
+
+    pub(crate) fn expect_symbol(&mut self, symbol: &str) -> SqlzResult<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("'{}'", symbol)))
+        }
+    }
+
+    /// an unquoted non reserved word, or a quoted identifier
+    pub(crate) fn ident(&mut self) -> SqlzResult<String> {
+        match &self.peek().token {
+            Token::Word(w) if !is_reserved(w) => {
+                let w = w.clone();
+                self.next();
+                Ok(w)
+            }
+            Token::Quoted(q) => {
+                let q = q.clone();
+                self.next();
+                Ok(q)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    /// dotted name, e.g. `schema.table` or `t1.c1`
+    pub(crate) fn qualified_ident(&mut self) -> SqlzResult<String> {
+        let mut name = self.ident()?;
+        while self.is_symbol(".") && !matches!(self.peek_nth(1).token, Token::Symbol("*")) {
+            self.next();
+            name.push('.');
+            name.push_str(&self.ident()?);
+        }
+        Ok(name)
+    }
+
+    /// comma separated identifiers within parentheses
+    pub(crate) fn ident_list(&mut self) -> SqlzResult<Vec<String>> {
+        self.expect_symbol("(")?;
+        let mut list = vec![self.ident()?];
+        while self.eat_symbol(",") {
+            list.push(self.ident()?);
+        }
+        self.expect_symbol(")")?;
+        Ok(list)
+    }
+
+    /// optional `;` followed by the end of input
+    pub(crate) fn end(&mut self) -> SqlzResult<()> {
+        self.eat_symbol(";");
+        match self.peek().token {
+            Token::Eof => Ok(()),
+            _ => Err(self.unexpected("end of statement")),
+        }
+    }
+
+    /// source text of a token
+    pub(crate) fn text(&self, t: &Spanned) -> &'a str {
+        &self.sql[t.start..t.end]
+    }
+
+    pub(crate) fn error_at(&self, t: &Spanned, message: String) -> SqlzError {
+        SqlzError::Parse(ParseError::new(message, t.start, t.end))
+    }
+
+    pub(crate) fn unexpected(&self, expected: &str) -> SqlzError {
+        let t = self.peek();
+        let found = match t.token {
+            Token::Eof => "end of input".to_owned(),
+            _ => format!("'{}'", self.text(t)),
+        };
+        self.error_at(t, format!("expected {}, found {}", expected, found))
+    }
+
+    pub(crate) fn unsupported(&self, what: &str) -> SqlzError {
+        self.error_at(self.peek(), format!("unsupported {}", what))
+    }
+}
+
+fn is_word(t: &Spanned, keyword: &str) -> bool {
+    matches!(&t.token, Token::Word(w) if w.eq_ignore_ascii_case(keyword))
+}
+
+fn is_reserved(word: &str) -> bool {
+    RESERVED.iter().any(|r| r.eq_ignore_ascii_case(word))
+}
+
+#[cfg(test)]
+mod tests_parser {
+    use super::*;
+
+    #[test]
+    fn statement() {
+        let sql = "-- pasted\nselect * /* all */ from t;";
+        assert!(matches!(parse(sql), Ok(Statement::Select(_))));
+        assert!(matches!(
+            parse("CREATE TABLE t (id INT)"),
+            Ok(Statement::TableCreate(_))
+        ));
+    }
+
+    #[test]
+    fn error_span() {
+        let sql = "DROP TABLE t";
+        assert_eq!(
+            parse(sql),
+            Err(SqlzError::Parse(ParseError::new(
+                "expected SELECT or CREATE TABLE, found 'DROP'",
+                0,
+                4
+            )))
+        );
+
+        let sql = "SELECT * FROM t WHERE c = 'abc";
+        assert_eq!(
+            parse(sql),
+            Err(SqlzError::Parse(ParseError::new(
+                "unterminated quote",
+                26,
+                30
+            )))
+        );
+
+        let sql = "SELECT * FROM t UNION SELECT * FROM s";
+        let err = parse(sql).unwrap_err();
+        assert_eq!(
+            err,
+            SqlzError::Parse(ParseError::new("unsupported UNION", 16, 21))
+        );
+        assert_eq!(err.to_string(), "parse error unsupported UNION at 16..21");
+    }
+}
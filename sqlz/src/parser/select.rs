@@ -0,0 +1,538 @@
+use super::lexer::Token;
+use super::Parser;
+use crate::{
+    Aggregate, AggregateFunction, ColumnAlias, Comparison, Condition, Conjunction, DataEnum,
+    Equation, Expression, Join, JoinType, Negation, Order, OrderType, Select, SqlzResult,
+    TableSource,
+};
+
+impl<'a> Parser<'a> {
+    pub(crate) fn select(&mut self) -> SqlzResult<Select> {
+        self.expect_keyword("SELECT")?;
+        if self.is_keyword("DISTINCT") || self.is_keyword("ALL") {
+            return Err(self.unsupported(&self.text(self.peek()).to_uppercase()));
+        }
+
+        let columns = if self.is_symbol("*") && self.peek_nth(1).token != Token::Symbol(",") {
+            self.next();
+            vec![]
+        } else {
+            let mut columns = vec![self.select_item()?];
+            while self.eat_symbol(",") {
+                columns.push(self.select_item()?);
+            }
+            columns
+        };
+
+        self.expect_keyword("FROM")?;
+        if self.is_symbol("(") {
+            return Err(self.unsupported("subquery"));
+        }
+        let table = self.qualified_ident()?;
+        let alias = self.alias()?;
+
+        let mut joins = vec![];
+        while let Some(join) = self.join()? {
+            joins.push(join);
+        }
+
+        let filter = if self.eat_keyword("WHERE") {
+            Some(self.expressions()?)
+        } else {
+            None
+        };
+
+        let group_by = if self.eat_keywords(&["GROUP", "BY"]) {
+            let mut list = vec![self.qualified_ident()?];
+            while self.eat_symbol(",") {
+                list.push(self.qualified_ident()?);
+            }
+            Some(list)
+        } else {
+            None
+        };
+
+        let having = if self.eat_keyword("HAVING") {
+            Some(self.expressions()?)
+        } else {
+            None
+        };
+
+        let order = if self.eat_keywords(&["ORDER", "BY"]) {
+            let mut list = vec![self.order()?];
+            while self.eat_symbol(",") {
+                list.push(self.order()?);
+            }
+            Some(list)
+        } else {
+            None
+        };
+
+        let (mut limit, mut offset) = (None, None);
+        if self.eat_keyword("LIMIT") {
+            let n = self.unsigned()?;
+            // MySql `LIMIT offset, count`
+            if self.eat_symbol(",") {
+                offset = Some(n);
+                limit = Some(self.unsigned()?);
+            } else {
+                limit = Some(n);
+            }
+        }
+        if offset.is_none() && self.eat_keyword("OFFSET") {
+            offset = Some(self.unsigned()?);
+        }
+
+        for k in ["UNION", "INTERSECT", "EXCEPT", "FETCH", "FOR", "WINDOW"] {
+            if self.is_keyword(k) {
+                return Err(self.unsupported(k));
+            }
+        }
+
+        Ok(Select {
+            with: None,
+            table: TableSource::Table(table),
+            alias,
+            columns,
+            join: if joins.is_empty() { None } else { Some(joins) },
+            filter,
+            group_by,
+            having,
+            order,
+            limit,
+            offset,
+            set_operations: None,
+            cursor: None,
+        })
+    }
+
+    fn select_item(&mut self) -> SqlzResult<ColumnAlias> {
+        if self.peek_nth(1).token == Token::Symbol("(") {
+            let aggregate = self.aggregate()?;
+            return Ok(ColumnAlias::Aggregate(Aggregate {
+                alias: self.alias()?,
+                ..aggregate
+            }));
+        }
+        let mut column = self.qualified_ident()?;
+        if self.is_symbol(".") {
+            // `t1.*`
+            self.next();
+            self.expect_symbol("*")?;
+            column.push_str(".*");
+        }
+        Ok(match self.alias()? {
+            Some(alias) => ColumnAlias::Alias((column, alias)),
+            None => ColumnAlias::Simple(column),
+        })
+    }
+
+    fn aggregate(&mut self) -> SqlzResult<Aggregate> {
+        let name = self.text(self.peek()).to_uppercase();
+        let function = match name.as_str() {
+            "COUNT" => AggregateFunction::Count,
+            "SUM" => AggregateFunction::Sum,
+            "AVG" => AggregateFunction::Avg,
+            "MIN" => AggregateFunction::Min,
+            "MAX" => AggregateFunction::Max,
+            _ => return Err(self.unsupported(&format!("function {}", name))),
+        };
+        self.next();
+        self.expect_symbol("(")?;
+        let aggregate = if function == AggregateFunction::Count && self.eat_symbol("*") {
+            Aggregate {
+                function,
+                column: "*".to_owned(),
+                alias: None,
+            }
+        } else if self.is_keyword("DISTINCT") {
+            if function != AggregateFunction::Count {
+                return Err(self.unsupported(&format!("DISTINCT in {}", name)));
+            }
+            self.next();
+            Aggregate {
+                function: AggregateFunction::CountDistinct,
+                column: self.qualified_ident()?,
+                alias: None,
+            }
+        } else {
+            Aggregate {
+                function,
+                column: self.qualified_ident()?,
+                alias: None,
+            }
+        };
+        self.expect_symbol(")")?;
+        Ok(aggregate)
+    }
+
+    /// `[AS] alias`
+    fn alias(&mut self) -> SqlzResult<Option<String>> {
+        if self.eat_keyword("AS") {
+            return Ok(Some(self.ident()?));
+        }
+        match &self.peek().token {
+            Token::Quoted(_) => Ok(Some(self.ident()?)),
+            Token::Word(_) => Ok(self.ident().ok()),
+            _ => Ok(None),
+        }
+    }
+
+    fn join(&mut self) -> SqlzResult<Option<Join>> {
+        if self.is_keyword("NATURAL") {
+            return Err(self.unsupported("NATURAL JOIN"));
+        }
+        let join_type = if self.eat_keyword("JOIN") || self.eat_keywords(&["INNER", "JOIN"]) {
+            JoinType::Inner
+        } else if self.eat_keyword("LEFT") {
+            JoinType::Left
+        } else if self.eat_keyword("RIGHT") {
+            JoinType::Right
+        } else if self.eat_keyword("FULL") {
+            JoinType::Full
+        } else if self.eat_keyword("CROSS") {
+            JoinType::Cross
+        } else if self.is_symbol(",") {
+            return Err(self.unsupported("comma join"));
+        } else {
+            return Ok(None);
+        };
+        if join_type != JoinType::Inner {
+            if join_type != JoinType::Cross {
+                self.eat_keyword("OUTER");
+            }
+            self.expect_keyword("JOIN")?;
+        }
+
+        if self.is_symbol("(") {
+            return Err(self.unsupported("subquery"));
+        }
+        let table = self.qualified_ident()?;
+        let alias = self.alias()?;
+        if self.is_keyword("USING") {
+            return Err(self.unsupported("USING"));
+        }
+        let on = if self.eat_keyword("ON") {
+            Some(self.expressions()?)
+        } else {
+            None
+        };
+
+        Ok(Some(Join {
+            join_type,
+            table: TableSource::Table(table),
+            alias,
+            on,
+        }))
+    }
+
+    fn order(&mut self) -> SqlzResult<Order> {
+        let name = self.qualified_ident()?;
+        let order = if self.eat_keyword("ASC") {
+            Some(OrderType::Asc)
+        } else if self.eat_keyword("DESC") {
+            Some(OrderType::Desc)
+        } else {
+            None
+        };
+        if self.is_keyword("NULLS") {
+            return Err(self.unsupported("NULLS FIRST/LAST"));
+        }
+        Ok(Order { name, order })
+    }
+
+    fn unsigned(&mut self) -> SqlzResult<u64> {
+        match &self.peek().token {
+            Token::Number(n) => match n.parse() {
+                Ok(n) => {
+                    self.next();
+                    Ok(n)
+                }
+                Err(_) => Err(self.unexpected("unsigned integer")),
+            },
+            _ => Err(self.unexpected("unsigned integer")),
+        }
+    }
+
+    /// conditions joined by `AND` and `OR`, in the flat filter form
+    pub(crate) fn expressions(&mut self) -> SqlzResult<Vec<Expression>> {
+        let mut list = vec![self.operand()?];
+        loop {
+            let conjunction = if self.eat_keyword("AND") {
+                Conjunction::AND
+            } else if self.eat_keyword("OR") {
+                Conjunction::OR
+            } else {
+                return Ok(list);
+            };
+            list.push(Expression::Conjunction(conjunction));
+            list.push(self.operand()?);
+        }
+    }
+
+    fn operand(&mut self) -> SqlzResult<Expression> {
+        if self.eat_keyword("NOT") {
+            let not = if self.is_symbol("(") {
+                self.nested()?
+            } else {
+                vec![self.operand()?]
+            };
+            return Ok(Expression::Not(Negation { not }));
+        }
+        if self.is_keyword("EXISTS") {
+            return Err(self.unsupported("EXISTS"));
+        }
+        if self.is_symbol("(") {
+            return Ok(Expression::Nest(self.nested()?));
+        }
+        Ok(Expression::Simple(self.condition()?))
+    }
+
+    fn nested(&mut self) -> SqlzResult<Vec<Expression>> {
+        self.expect_symbol("(")?;
+        if self.is_keyword("SELECT") {
+            return Err(self.unsupported("subquery"));
+        }
+        let list = self.expressions()?;
+        self.expect_symbol(")")?;
+        Ok(list)
+    }
+
+    fn condition(&mut self) -> SqlzResult<Condition> {
+        if !matches!(self.peek().token, Token::Word(_) | Token::Quoted(_)) {
+            return Err(self.unexpected("column"));
+        }
+        if self.peek_nth(1).token == Token::Symbol("(") {
+            let name = self.text(self.peek()).to_owned();
+            return Err(self.unsupported(&format!("function {} in condition", name)));
+        }
+        let column = self.qualified_ident()?;
+
+        let equation = if self.eat_keyword("IS") {
+            let not = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            if not {
+                Equation::IsNotNull
+            } else {
+                Equation::IsNull
+            }
+        } else if let Some(comparison) = self.comparison() {
+            if matches!(self.peek().token, Token::Word(_) | Token::Quoted(_))
+                && !self.is_literal_word()
+            {
+                Equation::Column((comparison, self.qualified_ident()?))
+            } else {
+                let value = self.value()?;
+                match comparison {
+                    Comparison::Equal => Equation::Equal(value),
+                    Comparison::NotEqual => Equation::NotEqual(value),
+                    Comparison::Greater => Equation::Greater(value),
+                    Comparison::GreaterEqual => Equation::GreaterEqual(value),
+                    Comparison::Less => Equation::Less(value),
+                    Comparison::LessEqual => Equation::LessEqual(value),
+                }
+            }
+        } else if self.eat_symbol("~") {
+            Equation::Regex(self.string()?)
+        } else if self.eat_symbol("!~") {
+            Equation::NotRegex(self.string()?)
+        } else {
+            let not = self.eat_keyword("NOT");
+            if self.eat_keyword("IN") {
+                self.expect_symbol("(")?;
+                if self.is_keyword("SELECT") {
+                    return Err(self.unsupported("subquery"));
+                }
+                let mut values = vec![self.value()?];
+                while self.eat_symbol(",") {
+                    values.push(self.value()?);
+                }
+                self.expect_symbol(")")?;
+                if not {
+                    Equation::NotIn(values)
+                } else {
+                    Equation::In(values)
+                }
+            } else if self.eat_keyword("BETWEEN") {
+                let low = self.value()?;
+                self.expect_keyword("AND")?;
+                let high = self.value()?;
+                if not {
+                    Equation::NotBetween((low, high))
+                } else {
+                    Equation::Between((low, high))
+                }
+            } else if self.eat_keyword("LIKE") {
+                let p = self.string()?;
+                if not {
+                    Equation::NotLike(p)
+                } else {
+                    Equation::Like(p)
+                }
+            } else if self.eat_keyword("ILIKE") {
+                let p = self.string()?;
+                if not {
+                    Equation::NotILike(p)
+                } else {
+                    Equation::ILike(p)
+                }
+            } else if self.eat_keyword("REGEXP") || self.eat_keyword("RLIKE") {
+                let p = self.string()?;
+                if not {
+                    Equation::NotRegex(p)
+                } else {
+                    Equation::Regex(p)
+                }
+            } else if not {
+                return Err(self.unexpected("IN, BETWEEN, LIKE, ILIKE or REGEXP"));
+            } else {
+                return Err(self.unexpected("operator"));
+            }
+        };
+
+        Ok(Condition { column, equation })
+    }
+
+    fn comparison(&mut self) -> Option<Comparison> {
+        let comparison = match &self.peek().token {
+            Token::Symbol("=") => Comparison::Equal,
+            Token::Symbol("<>") | Token::Symbol("!=") => Comparison::NotEqual,
+            Token::Symbol(">") => Comparison::Greater,
+            Token::Symbol(">=") => Comparison::GreaterEqual,
+            Token::Symbol("<") => Comparison::Less,
+            Token::Symbol("<=") => Comparison::LessEqual,
+            _ => return None,
+        };
+        self.next();
+        Some(comparison)
+    }
+
+    fn is_literal_word(&self) -> bool {
+        ["NULL", "TRUE", "FALSE"].iter().any(|k| self.is_keyword(k))
+    }
+
+    fn string(&mut self) -> SqlzResult<String> {
+        match &self.peek().token {
+            Token::Str(s) => {
+                let s = s.clone();
+                self.next();
+                Ok(s)
+            }
+            _ => Err(self.unexpected("string")),
+        }
+    }
+
+    /// literal value, a number can be negative
+    pub(crate) fn value(&mut self) -> SqlzResult<DataEnum> {
+        let negative = self.is_symbol("-") && matches!(self.peek_nth(1).token, Token::Number(_));
+        if negative {
+            self.next();
+        }
+        let t = self.peek().clone();
+        let value = match &t.token {
+            Token::Number(n) => {
+                let n = if negative {
+                    format!("-{}", n)
+                } else {
+                    n.clone()
+                };
+                match (n.parse::<i64>(), n.parse::<f64>()) {
+                    (Ok(i), _) => DataEnum::Integer(i),
+                    (_, Ok(f)) => DataEnum::Float(f),
+                    _ => return Err(self.error_at(&t, format!("invalid number '{}'", n))),
+                }
+            }
+            Token::Str(s) => DataEnum::String(s.clone()),
+            Token::Word(_) if self.is_keyword("NULL") => DataEnum::Null,
+            Token::Word(_) if self.is_keyword("TRUE") => DataEnum::Bool(true),
+            Token::Word(_) if self.is_keyword("FALSE") => DataEnum::Bool(false),
+            Token::Symbol("?") | Token::Symbol("$") => return Err(self.unsupported("placeholder")),
+            _ => return Err(self.unexpected("value")),
+        };
+        self.next();
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests_select_parser {
+    use crate::parser::{parse_select, ParseError};
+    use crate::{Dialect, SqlBuilder, SqlzError};
+
+    fn round_trip(sql: &str) -> String {
+        let select = parse_select(sql).unwrap();
+        SqlBuilder::new(Dialect::Postgres).select(&select).unwrap()
+    }
+
+    #[test]
+    fn parse_select_json() {
+        let sql = r#"SELECT c1, c2 AS c2_t FROM sqlz WHERE c1 BETWEEN 23 AND 25 OR c2 = 1 AND (c3 > 23 AND c4 IN ('T1', 'T2')) LIMIT 10 OFFSET 20"#;
+
+        let select = parse_select(sql).unwrap();
+
+        let json = r#"{"table":"sqlz","columns":["c1",["c2","c2_t"]],"filter":[{"column":"c1","equation":{"Between":[23,25]}},"OR",{"column":"c2","equation":{"Equal":1}},"AND",[{"column":"c3","equation":{"Greater":23}},"AND",{"column":"c4","equation":{"In":["T1","T2"]}}]],"order":null,"limit":10,"offset":20}"#;
+        assert_eq!(serde_json::to_string(&select).unwrap(), json);
+    }
+
+    #[test]
+    fn parse_select_round_trip() {
+        assert_eq!(
+            round_trip(
+                "select u.id, count(distinct p.id) as posts from \"public\".users u \
+                 left outer join posts as p on p.user_id = u.id \
+                 where u.name not like 'a''%' and not (u.age < -1.5 or u.tag is not null) \
+                 group by u.id having posts >= 2 order by u.id desc, posts limit 5, 10;"
+            ),
+            r#"SELECT "u"."id", COUNT(DISTINCT "p"."id") AS "posts" FROM "public"."users" AS "u" LEFT JOIN "posts" AS "p" ON "p"."user_id" = "u"."id" WHERE "u"."name" NOT LIKE 'a''%' AND NOT ("u"."age" < -1.5 OR "u"."tag" IS NOT NULL) GROUP BY "u"."id" HAVING COUNT(DISTINCT "p"."id") >= 2 ORDER BY "u"."id" DESC, "posts" LIMIT 10 OFFSET 5"#
+        );
+        assert_eq!(
+            round_trip("SELECT * FROM `t` WHERE `c` NOT IN (1, 2) AND d REGEXP '^a'"),
+            r#"SELECT * FROM "t" WHERE "c" NOT IN (1, 2) AND "d" ~ '^a'"#
+        );
+    }
+
+    #[test]
+    fn parse_select_error() {
+        fn error(sql: &str) -> (String, &str) {
+            match parse_select(sql) {
+                Err(SqlzError::Parse(ParseError {
+                    message,
+                    start,
+                    end,
+                })) => (message, &sql[start..end]),
+                r => panic!("unexpected {:?}", r),
+            }
+        }
+
+        assert_eq!(
+            error("SELECT DISTINCT c FROM t"),
+            ("unsupported DISTINCT".to_owned(), "DISTINCT")
+        );
+        assert_eq!(
+            error("SELECT c FROM t WHERE c IN (SELECT c FROM s)"),
+            ("unsupported subquery".to_owned(), "SELECT")
+        );
+        assert_eq!(
+            error("SELECT c FROM t WHERE lower(c) = 'a'"),
+            (
+                "unsupported function lower in condition".to_owned(),
+                "lower"
+            )
+        );
+        assert_eq!(
+            error("SELECT c FROM t WHERE c = ?"),
+            ("unsupported placeholder".to_owned(), "?")
+        );
+        assert_eq!(
+            error("SELECT c FROM t WHERE c = 1 AND"),
+            ("expected column, found end of input".to_owned(), "")
+        );
+        assert_eq!(
+            error("SELECT c FROM t ORDER c"),
+            (
+                "expected end of statement, found 'ORDER'".to_owned(),
+                "ORDER"
+            )
+        );
+    }
+}
@@ -0,0 +1,635 @@
+use super::lexer::Token;
+use super::Parser;
+use crate::{
+    Check, Column, ColumnDefault, ColumnKey, ColumnType, DataEnum, ForeignKey, ForeignKeyAction,
+    ForeignKeyDir, SqlzResult, TableCreate, UniqueKey,
+};
+
+impl<'a> Parser<'a> {
+    /// unnamed constraints are named as Postgres does, e.g. `t_c_fkey`.
+    /// Table options after the column list, e.g. MySql `ENGINE=InnoDB` or Sqlite
+    /// `WITHOUT ROWID`, are ignored except `COMMENT`.
+    pub(crate) fn table_create(&mut self) -> SqlzResult<TableCreate> {
+        self.expect_keyword("CREATE")?;
+        if !self.is_keyword("TABLE") {
+            return Err(self.unsupported(&format!("CREATE {}", self.text(self.peek()))));
+        }
+        self.next();
+        self.eat_keywords(&["IF", "NOT", "EXISTS"]);
+
+        let mut table = TableCreate {
+            name: self.qualified_ident()?,
+            ..Default::default()
+        };
+
+        if self.is_keyword("AS") {
+            return Err(self.unsupported("CREATE TABLE AS"));
+        }
+        self.expect_symbol("(")?;
+        loop {
+            self.table_element(&mut table)?;
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+        self.expect_symbol(")")?;
+
+        while self.table_option(&mut table)? {
+            self.eat_symbol(",");
+        }
+        if !self.is_symbol(";") && self.peek().token != Token::Eof {
+            return Err(self.unsupported(&format!("table option {}", self.text(self.peek()))));
+        }
+
+        Ok(table)
+    }
+
+    /// MySql and Sqlite table options, false if there is none
+    fn table_option(&mut self, table: &mut TableCreate) -> SqlzResult<bool> {
+        if self.eat_keyword("COMMENT") {
+            self.eat_symbol("=");
+            table.comment = Some(self.string_literal()?);
+        } else if self.eat_keyword("DEFAULT") {
+            if !(self.eat_keyword("CHARSET")
+                || self.eat_keywords(&["CHARACTER", "SET"])
+                || self.eat_keyword("COLLATE"))
+            {
+                return Err(self.unexpected("CHARSET or COLLATE"));
+            }
+            self.option_value()?;
+        } else if self.eat_keyword("ENGINE")
+            || self.eat_keyword("CHARSET")
+            || self.eat_keywords(&["CHARACTER", "SET"])
+            || self.eat_keyword("COLLATE")
+            || self.eat_keyword("AUTO_INCREMENT")
+            || self.eat_keyword("ROW_FORMAT")
+        {
+            self.option_value()?;
+        } else if !(self.eat_keywords(&["WITHOUT", "ROWID"]) || self.eat_keyword("STRICT")) {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// `[=] value` of a table option
+    fn option_value(&mut self) -> SqlzResult<()> {
+        self.eat_symbol("=");
+        match self.peek().token {
+            Token::Word(_) | Token::Quoted(_) | Token::Str(_) | Token::Number(_) => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(self.unexpected("table option value")),
+        }
+    }
+
+    fn table_element(&mut self, table: &mut TableCreate) -> SqlzResult<()> {
+        let name = if self.eat_keyword("CONSTRAINT") {
+            Some(self.ident()?)
+        } else {
+            None
+        };
+
+        if self.eat_keywords(&["PRIMARY", "KEY"]) {
+            table.primary_key = Some(self.ident_list()?);
+        } else if self.eat_keyword("UNIQUE") {
+            if !self.eat_keyword("KEY") {
+                self.eat_keyword("INDEX");
+            }
+            // MySql `UNIQUE KEY name (columns)`
+            let name = match name {
+                None if !self.is_symbol("(") => Some(self.ident()?),
+                name => name,
+            };
+            let columns = self.ident_list()?;
+            let name = name.unwrap_or_else(|| {
+                format!("{}_{}_key", unqualified(&table.name), columns.join("_"))
+            });
+            table
+                .unique_keys
+                .get_or_insert_with(Vec::new)
+                .push(UniqueKey { name, columns });
+        } else if self.eat_keywords(&["FOREIGN", "KEY"]) {
+            let columns = self.ident_list()?;
+            let name = name.unwrap_or_else(|| {
+                format!("{}_{}_fkey", unqualified(&table.name), columns.join("_"))
+            });
+            self.expect_keyword("REFERENCES")?;
+            let fk = self.references(name, &table.name, columns)?;
+            table.foreign_keys.push(fk);
+        } else if self.eat_keyword("CHECK") {
+            let name = name.unwrap_or_else(|| check_name(table, unqualified(&table.name)));
+            self.check(table, name)?;
+        } else if name.is_some() {
+            return Err(self.unexpected("PRIMARY KEY, UNIQUE, FOREIGN KEY or CHECK"));
+        } else if self.is_keyword("KEY") || self.is_keyword("INDEX") {
+            return Err(self.unsupported("index in CREATE TABLE"));
+        } else {
+            let column = self.column(table)?;
+            table.columns.push(column);
+        }
+        Ok(())
+    }
+
+    fn column(&mut self, table: &mut TableCreate) -> SqlzResult<Column> {
+        let mut column = Column {
+            name: self.ident()?,
+            ..Default::default()
+        };
+        let (col_type, serial) = self.column_type()?;
+        column.col_type = col_type;
+        if serial {
+            column.auto_increment = Some(true);
+        }
+
+        loop {
+            let name = if self.eat_keyword("CONSTRAINT") {
+                Some(self.ident()?)
+            } else {
+                None
+            };
+
+            if self.eat_keywords(&["NOT", "NULL"]) {
+                column.null = Some(false);
+            } else if self.eat_keyword("NULL") {
+                column.null = Some(true);
+            } else if self.eat_keywords(&["PRIMARY", "KEY"]) {
+                column.key = Some(ColumnKey::Primary);
+            } else if self.eat_keyword("UNIQUE") {
+                self.eat_keyword("KEY");
+                column.key = Some(ColumnKey::Unique);
+            } else if self.eat_keyword("DEFAULT") {
+                self.column_default(&mut column)?;
+            } else if self.eat_keyword("COMMENT") {
+                column.comment = Some(self.string_literal()?);
+            } else if self.eat_keyword("AUTO_INCREMENT") || self.eat_keyword("AUTOINCREMENT") {
+                column.auto_increment = Some(true);
+            } else if self.eat_keyword("GENERATED") {
+                if !self.eat_keyword("ALWAYS") {
+                    self.expect_keyword("BY")?;
+                    self.expect_keyword("DEFAULT")?;
+                }
+                self.expect_keyword("AS")?;
+                self.expect_keyword("IDENTITY")?;
+                column.auto_increment = Some(true);
+            } else if self.eat_keyword("REFERENCES") {
+                let name = name.unwrap_or_else(|| {
+                    format!("{}_{}_fkey", unqualified(&table.name), column.name)
+                });
+                let fk = self.references(name, &table.name, vec![column.name.clone()])?;
+                table.foreign_keys.push(fk);
+            } else if self.eat_keyword("CHECK") {
+                let name = name.unwrap_or_else(|| {
+                    check_name(
+                        table,
+                        &format!("{}_{}", unqualified(&table.name), column.name),
+                    )
+                });
+                self.check(table, name)?;
+            } else if self.eat_keyword("COLLATE")
+                || self.eat_keywords(&["CHARACTER", "SET"])
+                || self.eat_keyword("CHARSET")
+            {
+                self.ident()?;
+            } else if name.is_some() {
+                return Err(self.unexpected("column constraint"));
+            } else if self.is_symbol(",") || self.is_symbol(")") {
+                return Ok(column);
+            } else {
+                return Err(
+                    self.unsupported(&format!("column constraint {}", self.text(self.peek())))
+                );
+            }
+        }
+    }
+
+    /// `(type, serial)`, where serial types are integers with auto increment
+    fn column_type(&mut self) -> SqlzResult<(ColumnType, bool)> {
+        let t = self.peek().clone();
+        let name = match &t.token {
+            Token::Word(w) => w.to_lowercase(),
+            _ => return Err(self.unexpected("column type")),
+        };
+        self.next();
+
+        let mut serial = false;
+        let col_type = match name.as_str() {
+            "bool" | "boolean" => ColumnType::Bool,
+            "tinyint" | "smallint" | "int2" => self.skip_size(ColumnType::SmallInt)?,
+            "int" | "integer" | "int4" | "mediumint" => self.skip_size(ColumnType::Int)?,
+            "bigint" | "int8" => self.skip_size(ColumnType::BigInt)?,
+            "smallserial" | "serial2" => {
+                serial = true;
+                ColumnType::SmallInt
+            }
+            "serial" | "serial4" => {
+                serial = true;
+                ColumnType::Int
+            }
+            "bigserial" | "serial8" => {
+                serial = true;
+                ColumnType::BigInt
+            }
+            "real" | "float4" => ColumnType::Float,
+            // precision in bits, as in Postgres and MySql
+            "float" => match self.size()?.first() {
+                Some(p) if *p > 24 => ColumnType::Double,
+                _ => ColumnType::Float,
+            },
+            "double" => {
+                self.eat_keyword("PRECISION");
+                ColumnType::Double
+            }
+            "float8" => ColumnType::Double,
+            "decimal" | "numeric" => {
+                let size = self.size()?;
+                match size.as_slice() {
+                    [p] => ColumnType::Decimal(*p, 0),
+                    [p, s] => ColumnType::Decimal(*p, *s),
+                    _ => return Err(self.error_at(&t, "decimal without precision".to_owned())),
+                }
+            }
+            "date" => ColumnType::Date,
+            "time" => {
+                self.size()?;
+                self.time_zone()?;
+                ColumnType::Time
+            }
+            "datetime" => self.skip_size(ColumnType::DateTime)?,
+            "timestamp" => {
+                self.size()?;
+                if self.time_zone()? == Some(true) {
+                    ColumnType::Timestamp
+                } else {
+                    ColumnType::DateTime
+                }
+            }
+            "timestamptz" => self.skip_size(ColumnType::Timestamp)?,
+            "char" | "character" | "bpchar" | "nchar" => {
+                if self.eat_keyword("VARYING") {
+                    ColumnType::VarChar(self.single_size()?)
+                } else {
+                    ColumnType::Char(self.single_size()?)
+                }
+            }
+            "varchar" | "nvarchar" => ColumnType::VarChar(self.single_size()?),
+            "text" | "tinytext" | "mediumtext" | "longtext" => ColumnType::Text,
+            "json" | "jsonb" => ColumnType::Json,
+            "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" => {
+                self.skip_size(ColumnType::Binary)?
+            }
+            "uuid" => ColumnType::Uuid,
+            "enum" => {
+                self.expect_symbol("(")?;
+                let mut variants = vec![self.string_literal()?];
+                while self.eat_symbol(",") {
+                    variants.push(self.string_literal()?);
+                }
+                self.expect_symbol(")")?;
+                ColumnType::Enum(variants)
+            }
+            _ => return Err(self.error_at(&t, format!("unsupported column type {}", name))),
+        };
+
+        if self.is_keyword("UNSIGNED") {
+            return Err(self.unsupported("UNSIGNED"));
+        }
+
+        let mut col_type = col_type;
+        while self.is_symbol("[") {
+            self.next();
+            self.expect_symbol("]")?;
+            col_type = ColumnType::Array(Box::new(col_type));
+        }
+        Ok((col_type, serial))
+    }
+
+    /// optional `(n, ...)`
+    fn size(&mut self) -> SqlzResult<Vec<u32>> {
+        let mut size = vec![];
+        if self.eat_symbol("(") {
+            loop {
+                let t = self.peek().clone();
+                match &t.token {
+                    Token::Number(n) => match n.parse() {
+                        Ok(n) => size.push(n),
+                        Err(_) => return Err(self.unexpected("size")),
+                    },
+                    _ => return Err(self.unexpected("size")),
+                }
+                self.next();
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            self.expect_symbol(")")?;
+        }
+        Ok(size)
+    }
+
+    fn single_size(&mut self) -> SqlzResult<Option<u32>> {
+        match self.size()?.as_slice() {
+            [] => Ok(None),
+            [n] => Ok(Some(*n)),
+            _ => Err(self.unexpected("single size")),
+        }
+    }
+
+    /// sizes without a counterpart in `ColumnType`, e.g. display width, are dropped
+    fn skip_size(&mut self, col_type: ColumnType) -> SqlzResult<ColumnType> {
+        self.size()?;
+        Ok(col_type)
+    }
+
+    /// `Some(true)` for `WITH TIME ZONE`, `Some(false)` for `WITHOUT TIME ZONE`
+    fn time_zone(&mut self) -> SqlzResult<Option<bool>> {
+        let with = if self.eat_keyword("WITH") {
+            true
+        } else if self.eat_keyword("WITHOUT") {
+            false
+        } else {
+            return Ok(None);
+        };
+        self.expect_keyword("TIME")?;
+        self.expect_keyword("ZONE")?;
+        Ok(Some(with))
+    }
+
+    fn string_literal(&mut self) -> SqlzResult<String> {
+        match self.value()? {
+            DataEnum::String(s) => Ok(s),
+            _ => Err(self.unexpected("string")),
+        }
+    }
+
+    /// `DEFAULT value`, a Postgres cast such as `'a'::varchar` is dropped and
+    /// `nextval(...)` is taken as auto increment
+    fn column_default(&mut self, column: &mut Column) -> SqlzResult<()> {
+        let is_call = self.peek_nth(1).token == Token::Symbol("(");
+        let default = if self.eat_keyword("CURRENT_TIMESTAMP") || self.eat_keyword("NOW") {
+            ColumnDefault::CurrentTimestamp
+        } else if self.eat_keyword("CURRENT_DATE") {
+            ColumnDefault::CurrentDate
+        } else if self.eat_keyword("CURRENT_TIME") {
+            ColumnDefault::CurrentTime
+        } else if is_call && self.eat_keyword("NEXTVAL") {
+            self.expect_symbol("(")?;
+            self.value()?;
+            if self.eat_symbol("::") {
+                self.ident()?;
+            }
+            self.expect_symbol(")")?;
+            column.auto_increment = Some(true);
+            return Ok(());
+        } else if is_call {
+            let name = self.text(self.peek()).to_owned();
+            return Err(self.unsupported(&format!("function {} in default", name)));
+        } else {
+            let nested = self.eat_symbol("(");
+            let value = self.value()?;
+            if nested {
+                self.expect_symbol(")")?;
+            }
+            ColumnDefault::Value(value)
+        };
+        if is_call
+            && matches!(
+                default,
+                ColumnDefault::CurrentTimestamp
+                    | ColumnDefault::CurrentDate
+                    | ColumnDefault::CurrentTime
+            )
+            && self.eat_symbol("(")
+        {
+            self.expect_symbol(")")?;
+        }
+        if self.eat_symbol("::") {
+            self.column_type()?;
+        }
+        column.default = Some(default);
+        Ok(())
+    }
+
+    /// `table (columns) [ON DELETE action] [ON UPDATE action]` after `REFERENCES`
+    fn references(
+        &mut self,
+        name: String,
+        table: &str,
+        columns: Vec<String>,
+    ) -> SqlzResult<ForeignKey> {
+        let to_table = self.qualified_ident()?;
+        let to_columns = self.ident_list()?;
+        let mut fk = ForeignKey {
+            name,
+            from: ForeignKeyDir {
+                table: table.to_owned(),
+                columns,
+            },
+            to: ForeignKeyDir {
+                table: to_table,
+                columns: to_columns,
+            },
+            on_delete: ForeignKeyAction::default(),
+            on_update: ForeignKeyAction::default(),
+        };
+        loop {
+            if self.eat_keywords(&["ON", "DELETE"]) {
+                fk.on_delete = self.foreign_key_action()?;
+            } else if self.eat_keywords(&["ON", "UPDATE"]) {
+                fk.on_update = self.foreign_key_action()?;
+            } else {
+                return Ok(fk);
+            }
+        }
+    }
+
+    fn foreign_key_action(&mut self) -> SqlzResult<ForeignKeyAction> {
+        if self.eat_keyword("CASCADE") {
+            Ok(ForeignKeyAction::Cascade)
+        } else if self.eat_keyword("RESTRICT") {
+            Ok(ForeignKeyAction::Restrict)
+        } else if self.eat_keywords(&["SET", "NULL"]) {
+            Ok(ForeignKeyAction::SetNull)
+        } else if self.eat_keywords(&["SET", "DEFAULT"]) {
+            Ok(ForeignKeyAction::SetDefault)
+        } else if self.eat_keywords(&["NO", "ACTION"]) {
+            Ok(ForeignKeyAction::NoAction)
+        } else {
+            Err(self.unexpected("foreign key action"))
+        }
+    }
+
+    fn check(&mut self, table: &mut TableCreate, name: String) -> SqlzResult<()> {
+        self.expect_symbol("(")?;
+        let condition = self.expressions()?;
+        self.expect_symbol(")")?;
+        table
+            .checks
+            .get_or_insert_with(Vec::new)
+            .push(Check { name, condition });
+        Ok(())
+    }
+}
+
+/// native type name, e.g. `character varying(20)` or `integer[]`
+pub(crate) fn parse_column_type(sql: &str) -> SqlzResult<ColumnType> {
+    let mut p = Parser::new(sql)?;
+    let (col_type, _) = p.column_type()?;
+    p.end()?;
+    Ok(col_type)
+}
+
+/// default expression of a column, e.g. `'a'::character varying` or `now()`
+pub(crate) fn parse_column_default(sql: &str, column: &mut Column) -> SqlzResult<()> {
+    let mut p = Parser::new(sql)?;
+    p.column_default(column)?;
+    p.end()
+}
+
+fn unqualified(name: &str) -> &str {
+    name.rsplit('.').next().unwrap_or(name)
+}
+
+/// `<prefix>_check`, followed by a number if the name is taken
+fn check_name(table: &TableCreate, prefix: &str) -> String {
+    let taken = |name: &str| table.checks.iter().flatten().any(|c| c.name == name);
+    let name = format!("{}_check", prefix);
+    if !taken(&name) {
+        return name;
+    }
+    (1..)
+        .map(|n| format!("{}{}", name, n))
+        .find(|n| !taken(n))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests_table_parser {
+    use crate::parser::{parse_table_create, ParseError};
+    use crate::{ColumnType, Dialect, SqlBuilder, SqlzError};
+
+    #[test]
+    fn parse_postgres_ddl() {
+        let sql = r#"
+        CREATE TABLE public.orders (
+            id serial4 NOT NULL,
+            code varchar(20) DEFAULT 'new'::character varying NOT NULL,
+            amount numeric(10, 2) NULL CHECK (amount >= 0),
+            user_id int8 NOT NULL,
+            tags _text NULL,
+            created_at timestamp without time zone DEFAULT now() NOT NULL,
+            CONSTRAINT orders_pkey PRIMARY KEY (id),
+            CONSTRAINT orders_user_fk FOREIGN KEY (user_id) REFERENCES public.users(id) ON DELETE CASCADE
+        );"#;
+
+        let error = parse_table_create(sql).unwrap_err();
+        let start = sql.find("_text").unwrap();
+        assert_eq!(
+            error,
+            SqlzError::Parse(ParseError::new(
+                "unsupported column type _text",
+                start,
+                start + 5
+            ))
+        );
+
+        let sql = sql.replace("_text", "text[]");
+        let table = parse_table_create(&sql).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .table_create(&table)
+                .unwrap(),
+            vec![concat!(
+                r#"CREATE TABLE "public"."orders" ("id" INTEGER GENERATED BY DEFAULT AS IDENTITY NOT NULL PRIMARY KEY, "#,
+                r#""code" VARCHAR(20) NOT NULL DEFAULT 'new', "amount" NUMERIC(10, 2), "user_id" BIGINT NOT NULL, "#,
+                r#""tags" TEXT[], "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, "#,
+                r#"CONSTRAINT "orders_amount_check" CHECK ("amount" >= 0), "#,
+                r#"CONSTRAINT "orders_user_fk" FOREIGN KEY ("user_id") REFERENCES "public"."users" ("id") ON DELETE CASCADE ON UPDATE NO ACTION)"#
+            )]
+        );
+    }
+
+    #[test]
+    fn parse_mysql_ddl() {
+        let sql = "CREATE TABLE `users` (\n\
+            `id` int(11) NOT NULL AUTO_INCREMENT,\n\
+            `email` varchar(100) COLLATE utf8mb4_unicode_ci NOT NULL,\n\
+            `role` enum('admin','user') DEFAULT 'user',\n\
+            `parent_id` int REFERENCES users (id),\n\
+            PRIMARY KEY (`id`),\n\
+            UNIQUE KEY `uk_email` (`email`)\n\
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4";
+
+        let table = parse_table_create(sql).unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::MySql).table_create(&table).unwrap(),
+            vec![concat!(
+                "CREATE TABLE `users` (`id` INT NOT NULL AUTO_INCREMENT PRIMARY KEY, `email` VARCHAR(100) NOT NULL, ",
+                "`role` ENUM('admin', 'user') DEFAULT 'user', `parent_id` INT, ",
+                "CONSTRAINT `uk_email` UNIQUE (`email`), CONSTRAINT `users_parent_id_fkey` FOREIGN KEY (`parent_id`) ",
+                "REFERENCES `users` (`id`) ON DELETE NO ACTION ON UPDATE NO ACTION)"
+            )]
+        );
+
+        let sql = "CREATE TABLE t (id INT, KEY idx (id))";
+        assert_eq!(
+            parse_table_create(sql),
+            Err(SqlzError::Parse(ParseError::new(
+                "unsupported index in CREATE TABLE",
+                24,
+                27
+            )))
+        );
+    }
+
+    #[test]
+    fn parse_column_types() {
+        let table = parse_table_create(
+            "CREATE TABLE t (a smallserial, b serial, c bigserial, d float, e float(24), f float(53))",
+        )
+        .unwrap();
+        let types = table
+            .columns
+            .iter()
+            .map(|c| (c.col_type.clone(), c.auto_increment))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            vec![
+                (ColumnType::SmallInt, Some(true)),
+                (ColumnType::Int, Some(true)),
+                (ColumnType::BigInt, Some(true)),
+                (ColumnType::Float, None),
+                (ColumnType::Float, None),
+                (ColumnType::Double, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_comments() {
+        let sql =
+            "CREATE TABLE t (id INT COMMENT 'row id', name TEXT) ENGINE=InnoDB COMMENT='a table'";
+
+        let table = parse_table_create(sql).unwrap();
+
+        assert_eq!(table.comment, Some("a table".to_owned()));
+        assert_eq!(table.columns[0].comment, Some("row id".to_owned()));
+        assert_eq!(table.columns[1].comment, None);
+
+        let sql = "CREATE TABLE t (id INT) DEFAULT CHARSET=utf8mb4, AUTO_INCREMENT = 5 COLLATE utf8mb4_bin";
+        assert!(parse_table_create(sql).is_ok());
+        assert!(parse_table_create("CREATE TABLE t (id INTEGER) WITHOUT ROWID, STRICT;").is_ok());
+        let sql = "CREATE TABLE t (id int) garbage ( ) here";
+        assert_eq!(
+            parse_table_create(sql),
+            Err(SqlzError::Parse(ParseError::new(
+                "unsupported table option garbage",
+                24,
+                31
+            )))
+        );
+    }
+}
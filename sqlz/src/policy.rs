@@ -0,0 +1,861 @@
+//! Restricts untrusted selects to readable tables and columns of a schema.
+//!
+//! `Policy::apply` checks every table and column a select refers to, also in
+//! joins, subqueries, common table expressions and set operations, and gives
+//! the select to run: `limit` capped and mandatory filters, such as tenant
+//! filters, added to each read of a table.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ColumnAlias, ColumnExpr, Condition, Conjunction, Equation, Expression, Function, JoinType,
+    Negation, Schema, Select, SetOperation, SqlzError, SqlzResult, TableSource, Window,
+    WindowFunction,
+};
+
+/// readable tables, with a cap of `limit` and operators refused in conditions
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Policy {
+    pub tables: Vec<TablePolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forbidden_operators: Vec<Operator>,
+}
+
+/// a readable table. Unqualified columns of `filter` refer to this table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TablePolicy {
+    pub name: String,
+    /// readable columns, all columns of the table if none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    /// conditions added to every read of the table, e.g. a tenant filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Vec<Expression>>,
+}
+
+/// operator of a condition, named after `Equation`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    In,
+    Between,
+    Like,
+    Column,
+    IsNull,
+    IsNotNull,
+    NotIn,
+    NotBetween,
+    NotLike,
+    ILike,
+    NotILike,
+    Regex,
+    NotRegex,
+    InSelect,
+    NotInSelect,
+    Exists,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PolicyViolationKind {
+    /// a table which is not readable, or not in the schema
+    Table(String),
+    /// a column which is not readable, including `*` over hidden columns
+    Column(String),
+    Operator(Operator),
+}
+
+/// a violation located by a JSON pointer such as `/join/0/table`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PolicyViolation {
+    pub path: String,
+    pub kind: PolicyViolationKind,
+}
+
+impl Equation {
+    pub fn operator(&self) -> Operator {
+        match self {
+            Equation::Equal(_) => Operator::Equal,
+            Equation::NotEqual(_) => Operator::NotEqual,
+            Equation::Greater(_) => Operator::Greater,
+            Equation::GreaterEqual(_) => Operator::GreaterEqual,
+            Equation::Less(_) => Operator::Less,
+            Equation::LessEqual(_) => Operator::LessEqual,
+            Equation::In(_) => Operator::In,
+            Equation::Between(_) => Operator::Between,
+            Equation::Like(_) => Operator::Like,
+            Equation::Column(_) => Operator::Column,
+            Equation::IsNull => Operator::IsNull,
+            Equation::IsNotNull => Operator::IsNotNull,
+            Equation::NotIn(_) => Operator::NotIn,
+            Equation::NotBetween(_) => Operator::NotBetween,
+            Equation::NotLike(_) => Operator::NotLike,
+            Equation::ILike(_) => Operator::ILike,
+            Equation::NotILike(_) => Operator::NotILike,
+            Equation::Regex(_) => Operator::Regex,
+            Equation::NotRegex(_) => Operator::NotRegex,
+            Equation::InSelect(_) => Operator::InSelect,
+            Equation::NotInSelect(_) => Operator::NotInSelect,
+        }
+    }
+}
+
+impl Policy {
+    /// check the select against the policy and the schema, and give the
+    /// select to run. All violations are reported together.
+    pub fn apply(&self, schema: &Schema, select: &Select) -> SqlzResult<Select> {
+        select.check()?;
+        let mut select = select.clone();
+        let mut checker = Checker {
+            policy: self,
+            schema,
+            violations: vec![],
+        };
+        checker.select(&mut select, "", &[], &[]);
+        if !checker.violations.is_empty() {
+            return Err(SqlzError::Policy(checker.violations));
+        }
+        if let Some(max) = self.max_limit {
+            select.limit = Some(select.limit.map_or(max, |l| l.min(max)));
+        }
+        Ok(select)
+    }
+}
+
+/// a table in scope, referred to by any of `names`, with its readable
+/// `columns` out of `all` its columns. `depth` is the nesting of its select.
+#[derive(Debug, Clone)]
+struct Scope {
+    names: Vec<String>,
+    columns: Vec<String>,
+    all: Vec<String>,
+    depth: usize,
+}
+
+impl Scope {
+    fn hidden(&self) -> bool {
+        self.columns.len() < self.all.len()
+    }
+}
+
+struct Checker<'a> {
+    policy: &'a Policy,
+    schema: &'a Schema,
+    violations: Vec<PolicyViolation>,
+}
+
+impl<'a> Checker<'a> {
+    fn violation(&mut self, path: String, kind: PolicyViolationKind) {
+        self.violations.push(PolicyViolation { path, kind });
+    }
+
+    /// check a select and add mandatory filters, `outer` are the tables of
+    /// enclosing selects. Gives the output column names.
+    fn select(
+        &mut self,
+        select: &mut Select,
+        path: &str,
+        outer: &[Scope],
+        ctes: &[Scope],
+    ) -> Vec<String> {
+        let depth = outer.iter().map(|s| s.depth + 1).max().unwrap_or(0);
+        let mut ctes = ctes.to_vec();
+        for (i, cte) in select.with.iter_mut().flatten().enumerate() {
+            let p = format!("{}/with/{}/select", path, i);
+            let output = self.select(&mut cte.select, &p, &[], &ctes);
+            let columns = cte.columns.clone().unwrap_or(output);
+            ctes.push(Scope {
+                names: vec![cte.name.clone()],
+                all: columns.clone(),
+                columns,
+                depth: 0,
+            });
+        }
+
+        let mut local = vec![];
+        let mut added = None;
+        let p = format!("{}/table", path);
+        if let Some((scope, filter)) = self.source(&mut select.table, &select.alias, &p, &ctes) {
+            added = filter;
+            local.push(Scope { depth, ..scope });
+        }
+
+        for (i, join) in select.join.iter_mut().flatten().enumerate() {
+            let p = format!("{}/join/{}/table", path, i);
+            let source = self.source(&mut join.table, &join.alias, &p, &ctes);
+            if let Some((scope, _)) = &source {
+                local.push(Scope {
+                    depth,
+                    ..scope.clone()
+                });
+            }
+            if let Some(on) = &mut join.on {
+                let visible = [local.as_slice(), outer].concat();
+                let p = format!("{}/join/{}/on", path, i);
+                self.expressions(on, &p, &visible, None, &ctes);
+            }
+            // a cross join has no `ON`, and `ON` of a right or full join does
+            // not restrict the joined table, so their filters go to `WHERE`
+            let in_where = matches!(
+                join.join_type,
+                JoinType::Cross | JoinType::Right | JoinType::Full
+            );
+            match source {
+                Some((_, Some(f))) if in_where => {
+                    added = Some(and(added, f));
+                }
+                Some((_, Some(f))) => join.on = Some(and(join.on.take(), f)),
+                _ => {}
+            }
+        }
+        let visible = [local.as_slice(), outer].concat();
+
+        let mut output = vec![];
+        for (i, c) in select.columns.iter_mut().enumerate() {
+            let p = format!("{}/columns/{}", path, i);
+            output.extend(self.column_alias(c, &p, &local, &visible, &ctes));
+        }
+        if select.columns.is_empty() {
+            if local.iter().any(|s| s.hidden()) {
+                self.violation(
+                    format!("{}/columns", path),
+                    PolicyViolationKind::Column("*".to_owned()),
+                );
+            }
+            output.extend(local.iter().flat_map(|s| s.columns.clone()));
+        }
+
+        if let Some(filter) = &mut select.filter {
+            let p = format!("{}/filter", path);
+            self.expressions(filter, &p, &visible, None, &ctes);
+        }
+        // added filters are not subject to the policy, so they are added after checks
+        if let Some(f) = added {
+            select.filter = Some(and(select.filter.take(), f));
+        }
+        for (i, c) in select.group_by.iter().flatten().enumerate() {
+            if !resolve(c, &visible) {
+                self.violation(
+                    format!("{}/group_by/{}", path, i),
+                    PolicyViolationKind::Column(c.clone()),
+                );
+            }
+        }
+        if let Some(having) = &mut select.having {
+            let p = format!("{}/having", path);
+            self.expressions(having, &p, &visible, Some(&output), &ctes);
+        }
+        for (i, o) in select.order.iter().flatten().enumerate() {
+            // a cursor compares the source columns of `order` in `WHERE`
+            let readable = match &select.cursor {
+                Some(_) => match select.source_column(&o.name) {
+                    Ok(c) => resolve(c, &visible),
+                    // rejected by `keyset_filter`
+                    Err(_) => true,
+                },
+                None => output.contains(&o.name) || resolve(&o.name, &visible),
+            };
+            if !readable {
+                self.violation(
+                    format!("{}/order/{}/name", path, i),
+                    PolicyViolationKind::Column(o.name.clone()),
+                );
+            }
+        }
+
+        for (i, op) in select.set_operations.iter_mut().flatten().enumerate() {
+            let (name, operand) = match op {
+                SetOperation::Union(s) => ("Union", s),
+                SetOperation::UnionAll(s) => ("UnionAll", s),
+                SetOperation::Intersect(s) => ("Intersect", s),
+                SetOperation::Except(s) => ("Except", s),
+            };
+            let p = format!("{}/set_operations/{}/{}", path, i, name);
+            self.select(operand, &p, outer, &ctes);
+        }
+
+        output
+    }
+
+    /// scope of a table source, with its mandatory filter qualified by the
+    /// name the table is referred to
+    fn source(
+        &mut self,
+        table: &mut TableSource,
+        alias: &Option<String>,
+        path: &str,
+        ctes: &[Scope],
+    ) -> Option<(Scope, Option<Vec<Expression>>)> {
+        let name = match table {
+            TableSource::Derived(d) => {
+                let p = format!("{}/select", path);
+                let columns = self.select(&mut d.select, &p, &[], ctes);
+                let scope = Scope {
+                    names: alias.iter().cloned().collect(),
+                    all: columns.clone(),
+                    columns,
+                    depth: 0,
+                };
+                return Some((scope, None));
+            }
+            TableSource::Table(name) => name.clone(),
+        };
+
+        let names = match alias {
+            Some(a) => vec![a.clone()],
+            None => vec![name.clone(), unqualified(&name).to_owned()],
+        };
+        if let Some(cte) = ctes.iter().rev().find(|s| s.names[0] == name) {
+            let scope = Scope {
+                names,
+                ..cte.clone()
+            };
+            return Some((scope, None));
+        }
+
+        let in_schema = |t: &str| t == name || format!("{}.{}", self.schema.schema, t) == name;
+        let policy = self.policy.tables.iter().find(|t| in_schema(&t.name));
+        let table = self.schema.tables.iter().find(|t| in_schema(&t.name));
+        let (policy, table) = match (policy, table) {
+            (Some(p), Some(t)) => (p, t),
+            _ => {
+                self.violation(path.to_owned(), PolicyViolationKind::Table(name));
+                return None;
+            }
+        };
+
+        let columns = table
+            .columns
+            .iter()
+            .map(|c| c.name.clone())
+            .filter(|c| {
+                matches!(&policy.columns, Some(p) if p.contains(c)) || policy.columns.is_none()
+            })
+            .collect::<Vec<_>>();
+        let filter = policy
+            .filter
+            .as_ref()
+            .filter(|f| !f.is_empty())
+            .map(|f| qualify(f, &names[0]));
+        Some((
+            Scope {
+                names,
+                columns,
+                all: table.columns.iter().map(|c| c.name.clone()).collect(),
+                depth: 0,
+            },
+            filter,
+        ))
+    }
+
+    /// output names of a column
+    fn column_alias(
+        &mut self,
+        column: &mut ColumnAlias,
+        path: &str,
+        local: &[Scope],
+        visible: &[Scope],
+        ctes: &[Scope],
+    ) -> Vec<String> {
+        match column {
+            ColumnAlias::Simple(s) if s == "*" || s.ends_with(".*") => {
+                let qualifier = s.strip_suffix(".*");
+                let scopes = local
+                    .iter()
+                    .filter(|l| !matches!(qualifier, Some(q) if !l.names.iter().any(|n| n == q)))
+                    .collect::<Vec<_>>();
+                if scopes.is_empty() || scopes.iter().any(|s| s.hidden()) {
+                    self.violation(path.to_owned(), PolicyViolationKind::Column(s.clone()));
+                }
+                scopes.iter().flat_map(|s| s.columns.clone()).collect()
+            }
+            ColumnAlias::Simple(s) => {
+                self.column(s, path, visible);
+                vec![unqualified(s).to_owned()]
+            }
+            ColumnAlias::Alias((s, a)) => {
+                self.column(s, path, visible);
+                vec![a.clone()]
+            }
+            ColumnAlias::Aggregate(a) => {
+                if a.column != "*" {
+                    self.column(&a.column, path, visible);
+                }
+                a.alias.iter().cloned().collect()
+            }
+            ColumnAlias::Computed(c) => {
+                self.column_expr(&mut c.expr, path, visible, ctes);
+                c.alias.iter().cloned().collect()
+            }
+            ColumnAlias::Window(w) => {
+                self.window(w, path, visible);
+                w.alias.iter().cloned().collect()
+            }
+        }
+    }
+
+    fn column(&mut self, column: &str, path: &str, visible: &[Scope]) {
+        if !resolve(column, visible) {
+            self.violation(
+                path.to_owned(),
+                PolicyViolationKind::Column(column.to_owned()),
+            );
+        }
+    }
+
+    fn column_expr(
+        &mut self,
+        expr: &mut ColumnExpr,
+        path: &str,
+        visible: &[Scope],
+        ctes: &[Scope],
+    ) {
+        match expr {
+            ColumnExpr::Column(c) => self.column(c, path, visible),
+            ColumnExpr::Value(_) => {}
+            ColumnExpr::Arithmetic(a) => {
+                self.column_expr(&mut a.0, path, visible, ctes);
+                self.column_expr(&mut a.2, path, visible, ctes);
+            }
+            ColumnExpr::Case(c) => {
+                for w in c.when.iter_mut() {
+                    self.expressions(&mut w.condition, path, visible, None, ctes);
+                    self.column_expr(&mut w.then, path, visible, ctes);
+                }
+                if let Some(e) = &mut c.otherwise {
+                    self.column_expr(e, path, visible, ctes);
+                }
+            }
+            ColumnExpr::Coalesce(v) => {
+                for e in v.iter_mut() {
+                    self.column_expr(e, path, visible, ctes);
+                }
+            }
+            ColumnExpr::Cast(c) => self.column_expr(&mut c.0, path, visible, ctes),
+            ColumnExpr::Function(f) => match f {
+                Function::Upper(e)
+                | Function::Lower(e)
+                | Function::Trim(e)
+                | Function::Length(e) => self.column_expr(e, path, visible, ctes),
+                Function::Concat(v) => {
+                    for e in v.iter_mut() {
+                        self.column_expr(e, path, visible, ctes);
+                    }
+                }
+                Function::Substring(s) => self.column_expr(&mut s.0, path, visible, ctes),
+                Function::Extract(x) | Function::DateTrunc(x) => {
+                    self.column_expr(&mut x.1, path, visible, ctes)
+                }
+                Function::CurrentTimestamp => {}
+            },
+        }
+    }
+
+    fn window(&mut self, window: &Window, path: &str, visible: &[Scope]) {
+        match &window.window {
+            WindowFunction::Lag(s) | WindowFunction::Lead(s) => {
+                self.column(&s.column, path, visible)
+            }
+            WindowFunction::Aggregate((_, c)) if c != "*" => self.column(c, path, visible),
+            _ => {}
+        }
+        for c in window.partition_by.iter() {
+            self.column(c, path, visible);
+        }
+        for o in window.order.iter() {
+            self.column(&o.name, path, visible);
+        }
+    }
+
+    /// conditions of a filter, whose columns may also be `aliases` in `HAVING`
+    fn expressions(
+        &mut self,
+        exprs: &mut [Expression],
+        path: &str,
+        visible: &[Scope],
+        aliases: Option<&[String]>,
+        ctes: &[Scope],
+    ) {
+        for (i, e) in exprs.iter_mut().enumerate() {
+            let p = format!("{}/{}", path, i);
+            match e {
+                Expression::Conjunction(_) => {}
+                Expression::Simple(c) => self.condition(c, &p, visible, aliases, ctes),
+                Expression::Nest(v) => self.expressions(v, &p, visible, aliases, ctes),
+                Expression::Not(n) => {
+                    let p = format!("{}/not", p);
+                    self.expressions(&mut n.not, &p, visible, aliases, ctes)
+                }
+                Expression::Exists(e) => {
+                    let p = format!("{}/exists", p);
+                    self.operator(Operator::Exists, &p);
+                    self.select(&mut e.exists, &p, visible, ctes);
+                }
+            }
+        }
+    }
+
+    fn condition(
+        &mut self,
+        condition: &mut Condition,
+        path: &str,
+        visible: &[Scope],
+        aliases: Option<&[String]>,
+        ctes: &[Scope],
+    ) {
+        let readable =
+            |c: &str| matches!(aliases, Some(a) if a.iter().any(|a| a == c)) || resolve(c, visible);
+        if !readable(&condition.column) {
+            self.violation(
+                format!("{}/column", path),
+                PolicyViolationKind::Column(condition.column.clone()),
+            );
+        }
+        let p = format!("{}/equation", path);
+        self.operator(condition.equation.operator(), &p);
+        match &mut condition.equation {
+            Equation::Column((_, other)) if !readable(other) => {
+                self.violation(
+                    format!("{}/Column/1", p),
+                    PolicyViolationKind::Column(other.clone()),
+                );
+            }
+            Equation::InSelect(s) => {
+                self.select(s, &format!("{}/InSelect", p), visible, ctes);
+            }
+            Equation::NotInSelect(s) => {
+                self.select(s, &format!("{}/NotInSelect", p), visible, ctes);
+            }
+            _ => {}
+        }
+    }
+
+    fn operator(&mut self, operator: Operator, path: &str) {
+        if self.policy.forbidden_operators.contains(&operator) {
+            self.violation(path.to_owned(), PolicyViolationKind::Operator(operator));
+        }
+    }
+}
+
+fn unqualified(column: &str) -> &str {
+    column.rsplit('.').next().unwrap_or(column)
+}
+
+/// a qualified column is looked up in the innermost table of its qualifier.
+/// An unqualified one binds, as in Sql, to the innermost select with a table
+/// having the column, readable or not, and must be readable in those tables.
+fn resolve(column: &str, scopes: &[Scope]) -> bool {
+    if let Some((q, c)) = column.rsplit_once('.') {
+        return matches!(
+            scopes.iter().find(|s| s.names.iter().any(|n| n == q)),
+            Some(s) if s.columns.iter().any(|n| n == c)
+        );
+    }
+    let having = scopes
+        .iter()
+        .filter(|s| s.all.iter().any(|n| n == column))
+        .collect::<Vec<_>>();
+    match having.iter().map(|s| s.depth).max() {
+        Some(depth) => having
+            .iter()
+            .filter(|s| s.depth == depth)
+            .all(|s| s.columns.iter().any(|n| n == column)),
+        None => false,
+    }
+}
+
+/// `(filter) AND (added)`
+fn and(filter: Option<Vec<Expression>>, added: Vec<Expression>) -> Vec<Expression> {
+    match filter {
+        Some(f) if !f.is_empty() => vec![
+            Expression::Nest(f),
+            Expression::Conjunction(Conjunction::AND),
+            Expression::Nest(added),
+        ],
+        _ => added,
+    }
+}
+
+/// qualify unqualified columns of a filter by the table name
+fn qualify(filter: &[Expression], table: &str) -> Vec<Expression> {
+    let name = |c: &str| {
+        if c.contains('.') {
+            c.to_owned()
+        } else {
+            format!("{}.{}", table, c)
+        }
+    };
+    filter
+        .iter()
+        .map(|e| match e {
+            Expression::Simple(c) => Expression::Simple(Condition {
+                column: name(&c.column),
+                equation: match &c.equation {
+                    Equation::Column((cmp, other)) => Equation::Column((cmp.clone(), name(other))),
+                    eq => eq.clone(),
+                },
+            }),
+            Expression::Nest(v) => Expression::Nest(qualify(v, table)),
+            Expression::Not(n) => Expression::Not(Negation {
+                not: qualify(&n.not, table),
+            }),
+            e => e.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests_policy {
+    use super::*;
+    use crate::{Dialect, SqlBuilder};
+
+    const SCHEMA: &str = r#"
+    {
+        "schema": "public",
+        "tables": [
+            {
+                "name": "orders",
+                "columns": [
+                    {"name": "id", "col_type": "Int", "null": false, "key": "Primary"},
+                    {"name": "tenant_id", "col_type": "Int", "null": false, "key": null},
+                    {"name": "customer_id", "col_type": "Int", "null": false, "key": null},
+                    {"name": "amount", "col_type": "Double", "null": false, "key": null}
+                ]
+            },
+            {
+                "name": "customers",
+                "columns": [
+                    {"name": "id", "col_type": "Int", "null": false, "key": "Primary"},
+                    {"name": "tenant_id", "col_type": "Int", "null": false, "key": null},
+                    {"name": "name", "col_type": "Text", "null": false, "key": null},
+                    {"name": "email", "col_type": "Text", "null": false, "key": null}
+                ]
+            },
+            {
+                "name": "users",
+                "columns": [
+                    {"name": "id", "col_type": "Int", "null": false, "key": "Primary"},
+                    {"name": "email", "col_type": "Text", "null": false, "key": null}
+                ]
+            },
+            {
+                "name": "audit",
+                "columns": [{"name": "id", "col_type": "Int", "null": false, "key": "Primary"}]
+            }
+        ]
+    }"#;
+
+    const POLICY: &str = r#"
+    {
+        "tables": [
+            {"name": "orders", "filter": [{"column": "tenant_id", "equation": {"Equal": 7}}]},
+            {"name": "users"},
+            {
+                "name": "customers",
+                "columns": ["id", "name"],
+                "filter": [{"column": "tenant_id", "equation": {"Equal": 7}}]
+            }
+        ],
+        "max_limit": 100,
+        "forbidden_operators": ["Regex", "NotRegex"]
+    }"#;
+
+    fn apply(select: &str) -> SqlzResult<Select> {
+        let schema: Schema = serde_json::from_str(SCHEMA).unwrap();
+        let policy: Policy = serde_json::from_str(POLICY).unwrap();
+        policy.apply(&schema, &serde_json::from_str(select).unwrap())
+    }
+
+    #[test]
+    fn policy_apply() {
+        let select = apply(
+            r#"
+        {
+            "with": [{"name": "big", "select": {
+                "table": "orders", "columns": ["id", "customer_id"],
+                "filter": [{"column": "amount", "equation": {"Greater": 100}}],
+                "order": null, "limit": null, "offset": null
+            }}],
+            "table": "big",
+            "alias": "b",
+            "columns": ["b.id", "c.name"],
+            "join": [{"join_type": "Left", "table": "customers", "alias": "c",
+                "on": [{"column": "b.customer_id", "equation": {"Column": ["Equal", "c.id"]}}]}],
+            "filter": [{"column": "c.name", "equation": {"Like": "A%"}}],
+            "order": [{"name": "id", "order": null}],
+            "limit": 500,
+            "offset": null
+        }"#,
+        )
+        .unwrap();
+
+        assert_eq!(select.limit, Some(100));
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).select(&select).unwrap(),
+            r#"WITH "big" AS (SELECT "id", "customer_id" FROM "orders" WHERE ("amount" > 100) AND ("orders"."tenant_id" = 7)) SELECT "b"."id", "c"."name" FROM "big" AS "b" LEFT JOIN "customers" AS "c" ON ("b"."customer_id" = "c"."id") AND ("c"."tenant_id" = 7) WHERE "c"."name" LIKE 'A%' ORDER BY "id" LIMIT 100"#
+        );
+    }
+
+    #[test]
+    fn policy_violations() {
+        let res = apply(
+            r#"
+        {
+            "table": "customers",
+            "columns": [],
+            "join": [{"join_type": "Cross", "table": "audit", "alias": null, "on": null}],
+            "filter": [
+                {"column": "email", "equation": {"Like": "%@a.com"}},
+                "AND",
+                {"column": "name", "equation": {"Regex": "^a"}},
+                "AND",
+                {"column": "id", "equation": {"InSelect": {
+                    "table": "orders", "columns": ["secret"],
+                    "filter": null, "order": null, "limit": null, "offset": null
+                }}}
+            ],
+            "order": null,
+            "limit": null,
+            "offset": null
+        }"#,
+        );
+
+        let violation = |path: &str, kind| PolicyViolation {
+            path: path.to_owned(),
+            kind,
+        };
+        assert_eq!(
+            res,
+            Err(SqlzError::Policy(vec![
+                violation(
+                    "/join/0/table",
+                    PolicyViolationKind::Table("audit".to_owned())
+                ),
+                violation("/columns", PolicyViolationKind::Column("*".to_owned())),
+                violation(
+                    "/filter/0/column",
+                    PolicyViolationKind::Column("email".to_owned())
+                ),
+                violation(
+                    "/filter/2/equation",
+                    PolicyViolationKind::Operator(Operator::Regex)
+                ),
+                violation(
+                    "/filter/4/equation/InSelect/columns/0",
+                    PolicyViolationKind::Column("secret".to_owned())
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn policy_outer_joins() {
+        let select = apply(
+            r#"
+        {
+            "table": "orders",
+            "columns": ["customers.name"],
+            "join": [{"join_type": "Right", "table": "customers", "alias": null,
+                "on": [{"column": "orders.customer_id", "equation": {"Column": ["Equal", "customers.id"]}}]}],
+            "filter": null,
+            "order": null,
+            "limit": null,
+            "offset": null
+        }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres).select(&select).unwrap(),
+            r#"SELECT "customers"."name" FROM "orders" RIGHT JOIN "customers" ON "orders"."customer_id" = "customers"."id" WHERE ("orders"."tenant_id" = 7) AND ("customers"."tenant_id" = 7) LIMIT 100"#
+        );
+    }
+
+    #[test]
+    fn policy_cursor_order() {
+        let select = |order: &str| {
+            let cursor = crate::Cursor(vec![5.into()]).encode();
+            apply(&format!(
+                r#"{{"table": "customers", "columns": [["id", "email"]], "filter": null,
+                "order": [{{"name": "{}", "order": null}}], "limit": 10, "offset": null, "cursor": "{}"}}"#,
+                order, cursor
+            ))
+        };
+
+        // the alias is compared as its source column
+        assert_eq!(
+            SqlBuilder::new(Dialect::Postgres)
+                .select(&select("email").unwrap())
+                .unwrap(),
+            r#"SELECT "id" AS "email" FROM "customers" WHERE ("customers"."tenant_id" = 7) AND (("id" > 5)) ORDER BY "email" LIMIT 10"#
+        );
+        assert_eq!(
+            select("customers.email"),
+            Err(SqlzError::Policy(vec![PolicyViolation {
+                path: "/order/0/name".to_owned(),
+                kind: PolicyViolationKind::Column("customers.email".to_owned()),
+            }]))
+        );
+    }
+
+    #[test]
+    fn policy_subquery_columns() {
+        let violation = |path: &str, column: &str| {
+            Err(SqlzError::Policy(vec![PolicyViolation {
+                path: path.to_owned(),
+                kind: PolicyViolationKind::Column(column.to_owned()),
+            }]))
+        };
+
+        // `email` binds to the hidden column of the inner table, not to users
+        assert_eq!(
+            apply(
+                r#"
+            {
+                "table": "users", "columns": ["id"],
+                "filter": [{"exists": {
+                    "table": "customers", "columns": ["id"],
+                    "filter": [{"column": "email", "equation": {"Like": "a%"}}],
+                    "order": null, "limit": null, "offset": null
+                }}],
+                "order": null, "limit": null, "offset": null
+            }"#
+            ),
+            violation("/filter/0/exists/filter/0/column", "email")
+        );
+        assert_eq!(
+            apply(
+                r#"
+            {
+                "table": "users", "columns": ["id"],
+                "filter": [{"column": "id", "equation": {"InSelect": {
+                    "table": "customers", "columns": ["email"],
+                    "filter": null, "order": null, "limit": null, "offset": null
+                }}}],
+                "order": null, "limit": null, "offset": null
+            }"#
+            ),
+            violation("/filter/0/equation/InSelect/columns/0", "email")
+        );
+        // the outer column is still readable when qualified
+        assert!(apply(
+            r#"
+        {
+            "table": "users", "columns": ["id"],
+            "filter": [{"exists": {
+                "table": "customers", "columns": ["id"],
+                "filter": [{"column": "users.email", "equation": {"Column": ["Equal", "name"]}}],
+                "order": null, "limit": null, "offset": null
+            }}],
+            "order": null, "limit": null, "offset": null
+        }"#
+        )
+        .is_ok());
+    }
+}
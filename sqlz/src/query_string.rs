@@ -0,0 +1,801 @@
+//! Compact, URL-safe encoding of a select in a query string, in the style of
+//! PostgREST, e.g. `cols=c1,c2:alias&where=c1.between(23,25),or,c2.eq(1)&order=-c2&limit=10`.
+//!
+//! - `cols`: columns, `c:alias` for an alias and `sum(c):alias` for aggregates
+//! - `where` and `having`: conditions `column.operator(values)` with `and` or
+//!   `or` between them (`and` when omitted), `(...)` to nest and `not(...)` to negate
+//! - `group`: group by columns
+//! - `order`: columns, `-c` for descending and `+c` (`%2Bc`) for ascending
+//! - `limit`, `offset` and `cursor`
+//!
+//! Values are numbers, `true`, `false`, `null`, bare or `'quoted'` strings, and
+//! tagged typed values such as `date'2021-06-01'`. `@c` is the column `c` in a
+//! comparison, e.g. `c1.gt(@c2)`. The table is not part of the query string, as
+//! it usually is the path.
+
+use std::convert::TryFrom;
+
+use crate::{
+    Aggregate, AggregateFunction, ColumnAlias, Comparison, Condition, Conjunction, DataEnum,
+    Equation, Expression, Negation, Order, OrderType, ParseError, Select, SqlzError, SqlzResult,
+    TableSource,
+};
+
+impl Select {
+    /// parse a query string of a select on `table`. Parse errors are located in
+    /// the decoded value of the parameter named in the message.
+    pub fn from_query_string(table: &str, query: &str) -> SqlzResult<Select> {
+        let mut select = Select {
+            with: None,
+            table: table.into(),
+            alias: None,
+            columns: vec![],
+            join: None,
+            filter: None,
+            group_by: None,
+            having: None,
+            order: None,
+            limit: None,
+            offset: None,
+            set_operations: None,
+            cursor: None,
+        };
+        let mut seen = vec![];
+        let query = query.strip_prefix('?').unwrap_or(query);
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = match pair.find('=') {
+                Some(i) => (&pair[..i], &pair[i + 1..]),
+                None => (pair, ""),
+            };
+            let (key, value) = (decode(key)?, decode(value)?);
+            if seen.contains(&key) {
+                return Err(SqlzError::InvalidValue(format!(
+                    "duplicate query parameter {}",
+                    key
+                )));
+            }
+            let mut r = Reader {
+                key: &key,
+                s: &value,
+                pos: 0,
+            };
+            match key.as_str() {
+                "cols" => select.columns = r.list(Reader::column)?,
+                "where" => select.filter = Some(r.filter()?),
+                "group" => select.group_by = Some(r.list(|r| r.name().map(str::to_owned))?),
+                "having" => select.having = Some(r.filter()?),
+                "order" => select.order = Some(r.list(Reader::order)?),
+                "limit" => select.limit = Some(r.number()?),
+                "offset" => select.offset = Some(r.number()?),
+                "cursor" => {
+                    select.cursor = Some(value.clone());
+                    r.pos = value.len();
+                }
+                _ => return Err(SqlzError::Unsupported(format!("query parameter {}", key))),
+            }
+            r.end()?;
+            seen.push(key);
+        }
+        select.check()?;
+        Ok(select)
+    }
+
+    /// write the query string of a select, without the table. Selects with
+    /// `with`, an alias, joins, a derived table, set operations, computed or
+    /// window columns, or subqueries in filters are unsupported.
+    pub fn to_query_string(&self) -> SqlzResult<String> {
+        self.check()?;
+        if self.with.is_some()
+            || self.alias.is_some()
+            || self.join.is_some()
+            || self.set_operations.is_some()
+            || matches!(self.table, TableSource::Derived(_))
+        {
+            return Err(SqlzError::Unsupported(
+                "query string of a select with `with`, an alias, joins, a derived table or set operations"
+                    .to_owned(),
+            ));
+        }
+
+        let mut params = vec![];
+        if !self.columns.is_empty() {
+            let columns = self
+                .columns
+                .iter()
+                .map(write_column)
+                .collect::<SqlzResult<Vec<_>>>()?;
+            params.push(("cols", columns.join(",")));
+        }
+        if let Some(filter) = self.filter.as_ref().filter(|f| !f.is_empty()) {
+            params.push(("where", write_filter(filter)?));
+        }
+        if let Some(group_by) = &self.group_by {
+            let names = group_by
+                .iter()
+                .map(|n| write_name(n))
+                .collect::<SqlzResult<Vec<_>>>()?;
+            params.push(("group", names.join(",")));
+        }
+        if let Some(having) = self.having.as_ref().filter(|f| !f.is_empty()) {
+            params.push(("having", write_filter(having)?));
+        }
+        if let Some(order) = &self.order {
+            let orders = order
+                .iter()
+                .map(|o| {
+                    let sign = match o.order {
+                        Some(OrderType::Asc) => "+",
+                        Some(OrderType::Desc) => "-",
+                        None => "",
+                    };
+                    Ok(format!("{}{}", sign, write_name(&o.name)?))
+                })
+                .collect::<SqlzResult<Vec<_>>>()?;
+            params.push(("order", orders.join(",")));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(cursor) = &self.cursor {
+            params.push(("cursor", cursor.clone()));
+        }
+
+        Ok(params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, encode(v)))
+            .collect::<Vec<_>>()
+            .join("&"))
+    }
+}
+
+/// characters of column names, which are qualified by `.`
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '*' | '$')
+}
+
+/// characters ending a bare value
+fn is_special(c: char) -> bool {
+    matches!(c, ',' | '(' | ')' | '\'')
+}
+
+enum Arg {
+    Value(DataEnum),
+    Column(String),
+}
+
+struct Reader<'a> {
+    key: &'a str,
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, f: F) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        &self.s[start..self.pos]
+    }
+
+    fn error<S: Into<String>>(&self, message: S, start: usize) -> SqlzError {
+        ParseError::new(
+            format!("{} in {}", message.into(), self.key),
+            start,
+            self.pos,
+        )
+        .into()
+    }
+
+    /// error at the current character
+    fn unexpected(&self, expected: &str) -> SqlzError {
+        let end = self.pos + self.peek().map_or(0, char::len_utf8);
+        ParseError::new(
+            format!("expected {} in {}", expected, self.key),
+            self.pos,
+            end,
+        )
+        .into()
+    }
+
+    fn expect(&mut self, c: char) -> SqlzResult<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{:?}", c)))
+        }
+    }
+
+    fn end(&self) -> SqlzResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("end")),
+        }
+    }
+
+    fn name(&mut self) -> SqlzResult<&'a str> {
+        let name = self.take_while(is_name_char);
+        if name.is_empty() {
+            return Err(self.unexpected("column"));
+        }
+        Ok(name)
+    }
+
+    fn number(&mut self) -> SqlzResult<u64> {
+        let start = self.pos;
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        digits
+            .parse()
+            .map_err(|_| self.error("expected number", start))
+    }
+
+    /// comma separated items, an empty value is an empty list
+    fn list<T, F>(&mut self, f: F) -> SqlzResult<Vec<T>>
+    where
+        F: Fn(&mut Self) -> SqlzResult<T>,
+    {
+        let mut items = vec![];
+        if self.peek().is_none() {
+            return Ok(items);
+        }
+        loop {
+            items.push(f(self)?);
+            if !self.eat(',') {
+                return Ok(items);
+            }
+        }
+    }
+
+    fn column(&mut self) -> SqlzResult<ColumnAlias> {
+        let start = self.pos;
+        let name = self.name()?;
+        if self.eat('(') {
+            let function = match name {
+                "count" => AggregateFunction::Count,
+                "count_distinct" => AggregateFunction::CountDistinct,
+                "sum" => AggregateFunction::Sum,
+                "avg" => AggregateFunction::Avg,
+                "min" => AggregateFunction::Min,
+                "max" => AggregateFunction::Max,
+                _ => return Err(self.error(format!("unknown aggregate {}", name), start)),
+            };
+            let column = self.name()?.to_owned();
+            self.expect(')')?;
+            let alias = if self.eat(':') {
+                Some(self.name()?.to_owned())
+            } else {
+                None
+            };
+            return Ok(ColumnAlias::Aggregate(Aggregate {
+                function,
+                column,
+                alias,
+            }));
+        }
+        if self.eat(':') {
+            Ok(ColumnAlias::Alias((
+                name.to_owned(),
+                self.name()?.to_owned(),
+            )))
+        } else {
+            Ok(ColumnAlias::Simple(name.to_owned()))
+        }
+    }
+
+    fn order(&mut self) -> SqlzResult<Order> {
+        let order = if self.eat('-') {
+            Some(OrderType::Desc)
+        } else if self.eat('+') {
+            Some(OrderType::Asc)
+        } else {
+            None
+        };
+        Ok(Order {
+            name: self.name()?.to_owned(),
+            order,
+        })
+    }
+
+    /// elements separated by commas, with `AND` between adjacent conditions
+    fn filter(&mut self) -> SqlzResult<Vec<Expression>> {
+        let mut exprs = self.list(Reader::element)?;
+        let mut i = 1;
+        while i < exprs.len() {
+            if !matches!(exprs[i - 1], Expression::Conjunction(_))
+                && !matches!(exprs[i], Expression::Conjunction(_))
+            {
+                exprs.insert(i, Expression::Conjunction(Conjunction::AND));
+            }
+            i += 1;
+        }
+        Ok(exprs)
+    }
+
+    fn element(&mut self) -> SqlzResult<Expression> {
+        if self.eat('(') {
+            let nest = self.filter()?;
+            self.expect(')')?;
+            return Ok(Expression::Nest(nest));
+        }
+        let start = self.pos;
+        let name = self.name()?;
+        match name {
+            "not" if self.eat('(') => {
+                let not = self.filter()?;
+                self.expect(')')?;
+                return Ok(Expression::Not(Negation { not }));
+            }
+            "and" if self.peek() != Some('(') => {
+                return Ok(Expression::Conjunction(Conjunction::AND))
+            }
+            "or" if self.peek() != Some('(') => {
+                return Ok(Expression::Conjunction(Conjunction::OR))
+            }
+            _ => {}
+        }
+        let (column, operator) = match name.rfind('.') {
+            Some(i) if i > 0 => (&name[..i], &name[i + 1..]),
+            _ => return Err(self.error("expected column.operator", start)),
+        };
+        self.expect('(')?;
+        let args = match self.peek() {
+            Some(')') => vec![],
+            _ => self.list(Reader::arg)?,
+        };
+        self.expect(')')?;
+        let equation = equation(operator, args).map_err(|message| self.error(message, start))?;
+        Ok(Expression::Simple(Condition {
+            column: column.to_owned(),
+            equation,
+        }))
+    }
+
+    fn arg(&mut self) -> SqlzResult<Arg> {
+        let start = self.pos;
+        if self.eat('@') {
+            return Ok(Arg::Column(self.name()?.to_owned()));
+        }
+        if self.peek() == Some('\'') {
+            return Ok(Arg::Value(DataEnum::String(self.quoted()?)));
+        }
+        let bare = self.take_while(|c| !is_special(c));
+        if self.peek() == Some('\'') {
+            let text = self.quoted()?;
+            return typed_value(bare, text)
+                .map(Arg::Value)
+                .ok_or_else(|| self.error(format!("invalid {} value", bare), start));
+        }
+        match bare_value(bare) {
+            Some(v) => Ok(Arg::Value(v)),
+            None => Err(self.unexpected("value")),
+        }
+    }
+
+    /// `'...'` where `''` is a quote
+    fn quoted(&mut self) -> SqlzResult<String> {
+        let start = self.pos;
+        self.expect('\'')?;
+        let mut res = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string", start)),
+                Some('\'') => {
+                    self.pos += 1;
+                    if !self.eat('\'') {
+                        return Ok(res);
+                    }
+                    res.push('\'');
+                }
+                Some(c) => {
+                    self.pos += c.len_utf8();
+                    res.push(c);
+                }
+            }
+        }
+    }
+}
+
+fn equation(operator: &str, args: Vec<Arg>) -> Result<Equation, String> {
+    let comparison = match operator {
+        "eq" => Some(Comparison::Equal),
+        "neq" => Some(Comparison::NotEqual),
+        "gt" => Some(Comparison::Greater),
+        "gte" => Some(Comparison::GreaterEqual),
+        "lt" => Some(Comparison::Less),
+        "lte" => Some(Comparison::LessEqual),
+        _ => None,
+    };
+    let invalid = || format!("invalid arguments of {}", operator);
+    let mut values = vec![];
+    let len = args.len();
+    for arg in args {
+        match (arg, &comparison) {
+            // a column comparison takes the column as its only argument
+            (Arg::Column(c), Some(comparison)) if len == 1 => {
+                return Ok(Equation::Column((comparison.clone(), c)))
+            }
+            (Arg::Value(v), _) => values.push(v),
+            _ => return Err(invalid()),
+        }
+    }
+    let one = |values: Vec<DataEnum>| match <[DataEnum; 1]>::try_from(values) {
+        Ok([v]) => Ok(v),
+        Err(_) => Err(invalid()),
+    };
+    let two = |values: Vec<DataEnum>| match <[DataEnum; 2]>::try_from(values) {
+        Ok([a, b]) => Ok((a, b)),
+        Err(_) => Err(invalid()),
+    };
+    let text = |values: Vec<DataEnum>| match one(values)? {
+        DataEnum::String(s) => Ok(s),
+        _ => Err(invalid()),
+    };
+    Ok(match operator {
+        "eq" => Equation::Equal(one(values)?),
+        "neq" => Equation::NotEqual(one(values)?),
+        "gt" => Equation::Greater(one(values)?),
+        "gte" => Equation::GreaterEqual(one(values)?),
+        "lt" => Equation::Less(one(values)?),
+        "lte" => Equation::LessEqual(one(values)?),
+        "in" => Equation::In(values),
+        "nin" => Equation::NotIn(values),
+        "between" => Equation::Between(two(values)?),
+        "nbetween" => Equation::NotBetween(two(values)?),
+        "like" => Equation::Like(text(values)?),
+        "nlike" => Equation::NotLike(text(values)?),
+        "ilike" => Equation::ILike(text(values)?),
+        "nilike" => Equation::NotILike(text(values)?),
+        "regex" => Equation::Regex(text(values)?),
+        "nregex" => Equation::NotRegex(text(values)?),
+        "is_null" if values.is_empty() => Equation::IsNull,
+        "not_null" if values.is_empty() => Equation::IsNotNull,
+        "is_null" | "not_null" => return Err(invalid()),
+        _ => return Err(format!("unknown operator {}", operator)),
+    })
+}
+
+/// number, boolean, `null` or string, `None` if empty
+fn bare_value(s: &str) -> Option<DataEnum> {
+    let numeric = s.chars().all(|c| c.is_ascii_digit() || "+-.eE".contains(c));
+    Some(match s {
+        "" => return None,
+        "true" => DataEnum::Bool(true),
+        "false" => DataEnum::Bool(false),
+        "null" => DataEnum::Null,
+        _ => match (s.parse::<i64>(), s.parse::<f64>()) {
+            (Ok(i), _) => DataEnum::Integer(i),
+            (_, Ok(f)) if numeric => DataEnum::Float(f),
+            _ => DataEnum::String(s.to_owned()),
+        },
+    })
+}
+
+/// typed value of a tag such as `date`, read by the serde form of `DataEnum`
+fn typed_value(tag: &str, text: String) -> Option<DataEnum> {
+    let tag = match tag {
+        "date" => "Date",
+        "datetime" => "DateTime",
+        "time" => "Time",
+        "decimal" => "Decimal",
+        "bytes" => "Bytes",
+        "uuid" => "Uuid",
+        "json" => "Json",
+        _ => return None,
+    };
+    let value = match tag {
+        "Json" => serde_json::from_str(&text).ok()?,
+        _ => serde_json::Value::String(text),
+    };
+    let mut object = serde_json::Map::new();
+    object.insert(tag.to_owned(), value);
+    serde_json::from_value(serde_json::Value::Object(object)).ok()
+}
+
+fn write_name(name: &str) -> SqlzResult<&str> {
+    if name.is_empty() || !name.chars().all(is_name_char) {
+        return Err(SqlzError::InvalidValue(format!(
+            "column {:?} in a query string",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+fn write_column(column: &ColumnAlias) -> SqlzResult<String> {
+    Ok(match column {
+        ColumnAlias::Simple(c) => write_name(c)?.to_owned(),
+        ColumnAlias::Alias((c, a)) => format!("{}:{}", write_name(c)?, write_name(a)?),
+        ColumnAlias::Aggregate(a) => {
+            let function = match a.function {
+                AggregateFunction::Count => "count",
+                AggregateFunction::CountDistinct => "count_distinct",
+                AggregateFunction::Sum => "sum",
+                AggregateFunction::Avg => "avg",
+                AggregateFunction::Min => "min",
+                AggregateFunction::Max => "max",
+            };
+            let mut res = format!("{}({})", function, write_name(&a.column)?);
+            if let Some(alias) = &a.alias {
+                res.push(':');
+                res.push_str(write_name(alias)?);
+            }
+            res
+        }
+        ColumnAlias::Computed(_) | ColumnAlias::Window(_) => {
+            return Err(SqlzError::Unsupported(
+                "query string of computed or window columns".to_owned(),
+            ))
+        }
+    })
+}
+
+/// a valid filter, `AND` is left out
+fn write_filter(exprs: &[Expression]) -> SqlzResult<String> {
+    let mut parts = vec![];
+    for e in exprs.iter() {
+        match e {
+            Expression::Conjunction(Conjunction::AND) => {}
+            Expression::Conjunction(Conjunction::OR) => parts.push("or".to_owned()),
+            Expression::Simple(c) => parts.push(write_condition(c)?),
+            Expression::Nest(v) => parts.push(format!("({})", write_filter(v)?)),
+            Expression::Not(n) => parts.push(format!("not({})", write_filter(&n.not)?)),
+            Expression::Exists(_) => {
+                return Err(SqlzError::Unsupported("query string of EXISTS".to_owned()))
+            }
+        }
+    }
+    Ok(parts.join(","))
+}
+
+fn write_condition(condition: &Condition) -> SqlzResult<String> {
+    let values = |values: &[&DataEnum]| {
+        values
+            .iter()
+            .map(|v| write_value(v))
+            .collect::<SqlzResult<Vec<_>>>()
+            .map(|v| v.join(","))
+    };
+    let string = |s: &String| write_value(&DataEnum::String(s.clone()));
+    let (operator, args) = match &condition.equation {
+        Equation::Equal(v) => ("eq", values(&[v])?),
+        Equation::NotEqual(v) => ("neq", values(&[v])?),
+        Equation::Greater(v) => ("gt", values(&[v])?),
+        Equation::GreaterEqual(v) => ("gte", values(&[v])?),
+        Equation::Less(v) => ("lt", values(&[v])?),
+        Equation::LessEqual(v) => ("lte", values(&[v])?),
+        Equation::In(v) => ("in", values(&v.iter().collect::<Vec<_>>())?),
+        Equation::NotIn(v) => ("nin", values(&v.iter().collect::<Vec<_>>())?),
+        Equation::Between((a, b)) => ("between", values(&[a, b])?),
+        Equation::NotBetween((a, b)) => ("nbetween", values(&[a, b])?),
+        Equation::Like(s) => ("like", string(s)?),
+        Equation::NotLike(s) => ("nlike", string(s)?),
+        Equation::ILike(s) => ("ilike", string(s)?),
+        Equation::NotILike(s) => ("nilike", string(s)?),
+        Equation::Regex(s) => ("regex", string(s)?),
+        Equation::NotRegex(s) => ("nregex", string(s)?),
+        Equation::IsNull => ("is_null", String::new()),
+        Equation::IsNotNull => ("not_null", String::new()),
+        Equation::Column((comparison, c)) => {
+            let operator = match comparison {
+                Comparison::Equal => "eq",
+                Comparison::NotEqual => "neq",
+                Comparison::Greater => "gt",
+                Comparison::GreaterEqual => "gte",
+                Comparison::Less => "lt",
+                Comparison::LessEqual => "lte",
+            };
+            (operator, format!("@{}", write_name(c)?))
+        }
+        Equation::InSelect(_) | Equation::NotInSelect(_) => {
+            return Err(SqlzError::Unsupported(
+                "query string of a subquery".to_owned(),
+            ))
+        }
+    };
+    Ok(format!(
+        "{}.{}({})",
+        write_name(&condition.column)?,
+        operator,
+        args
+    ))
+}
+
+fn write_value(value: &DataEnum) -> SqlzResult<String> {
+    Ok(match value {
+        DataEnum::Integer(v) => v.to_string(),
+        DataEnum::Float(v) if v.is_finite() => format!("{:?}", v),
+        DataEnum::Float(v) => {
+            return Err(SqlzError::InvalidValue(format!("non-finite float {}", v)))
+        }
+        DataEnum::Bool(v) => v.to_string(),
+        DataEnum::Null => "null".to_owned(),
+        DataEnum::String(s) => {
+            let bare = bare_value(s) == Some(value.clone())
+                && !s.starts_with('@')
+                && !s.chars().any(is_special);
+            if bare {
+                s.clone()
+            } else {
+                quote(s)
+            }
+        }
+        typed => {
+            let json =
+                serde_json::to_value(typed).map_err(|e| SqlzError::InvalidValue(e.to_string()))?;
+            match json {
+                serde_json::Value::Object(o) if o.len() == 1 => {
+                    let (tag, v) = o.into_iter().next().unwrap();
+                    let text = match v {
+                        serde_json::Value::String(s) if tag != "Json" => s,
+                        v => v.to_string(),
+                    };
+                    format!("{}{}", tag.to_lowercase(), quote(&text))
+                }
+                _ => return Err(SqlzError::InvalidValue(format!("{:?}", typed))),
+            }
+        }
+    })
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// percent-encode all but unreserved characters and the delimiters of the format
+fn encode(s: &str) -> String {
+    let mut res = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-_.~,()':*@!".contains(&b) {
+            res.push(b as char);
+        } else {
+            res.push_str(&format!("%{:02X}", b));
+        }
+    }
+    res
+}
+
+/// percent-decode, where `+` is a space
+fn decode(s: &str) -> SqlzResult<String> {
+    let invalid = || SqlzError::InvalidValue(format!("percent-encoding {}", s));
+    let bytes = s.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => res.push(b' '),
+            b'%' => {
+                let hex = s.get(i + 1..i + 3).ok_or_else(invalid)?;
+                res.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                i += 2;
+            }
+            b => res.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(res).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests_query_string {
+    use super::*;
+
+    #[test]
+    fn query_string_round_trip() {
+        let query = "cols=c1,c2:alias,count(*):n&where=c1.between(23,25),or,c2.eq(1),(c3.gt(@c4),or,not(c4.in(T1,T%202,'1',''))),c5.is_null()&order=-c2,c1,%2Bc3&limit=10&offset=20";
+
+        let select = Select::from_query_string("t", query).unwrap();
+
+        let expected: Select = serde_json::from_str(
+            r#"
+            {
+                "table": "t",
+                "columns": ["c1", ["c2", "alias"], {"function": "Count", "column": "*", "alias": "n"}],
+                "filter": [
+                    {"column":"c1","equation":{"Between":[23,25]}},
+                    "OR",
+                    {"column":"c2","equation":{"Equal":1}},
+                    "AND",
+                    [
+                        {"column":"c3","equation":{"Column":["Greater","c4"]}},
+                        "OR",
+                        {"not": [{"column":"c4","equation":{"In":["T1","T 2","1",""]}}]}
+                    ],
+                    "AND",
+                    {"column":"c5","equation":"IsNull"}
+                ],
+                "order": [
+                    {"name":"c2","order":"Desc"},
+                    {"name":"c1","order":null},
+                    {"name":"c3","order":"Asc"}
+                ],
+                "limit": 10,
+                "offset": 20
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(select, expected);
+        assert_eq!(select.to_query_string().unwrap(), query);
+
+        let query = "cols=c1.x&where=d.gte(date'2021-06-01'),p.lt(decimal'12.50'),j.eq(json'%7B%22a%22:%5B1%5D%7D'),s.like('it''s%25'),f.eq(1.0),g.neq(-2e-5)&group=c1.x&having=n.gt(1)";
+        let select = Select::from_query_string("t", query).unwrap();
+        assert_eq!(
+            select.filter.as_ref().unwrap()[0],
+            Expression::Simple(Condition {
+                column: "d".to_owned(),
+                equation: Equation::GreaterEqual(
+                    serde_json::from_str(r#"{"Date":"2021-06-01"}"#).unwrap()
+                ),
+            })
+        );
+        assert_eq!(select.to_query_string().unwrap(), query);
+        let json = serde_json::to_string(&select).unwrap();
+        let deserialized: Select = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.to_query_string().unwrap(), query);
+    }
+
+    #[test]
+    fn query_string_errors() {
+        assert_eq!(
+            Select::from_query_string("t", "limit=10&where=c1.eq(1),c2.eq(1"),
+            Err(SqlzError::Parse(ParseError::new(
+                "expected ')' in where",
+                16,
+                16
+            )))
+        );
+        assert_eq!(
+            Select::from_query_string("t", "where=c1.between(1)"),
+            Err(SqlzError::Parse(ParseError::new(
+                "invalid arguments of between in where",
+                0,
+                13
+            )))
+        );
+        assert_eq!(
+            Select::from_query_string("t", "where=c1.gt(@c2,5)"),
+            Err(SqlzError::Parse(ParseError::new(
+                "invalid arguments of gt in where",
+                0,
+                12
+            )))
+        );
+        assert!(matches!(
+            Select::from_query_string("t", "where=or,c1.eq(1)"),
+            Err(SqlzError::InvalidFilter(_))
+        ));
+        assert!(matches!(
+            Select::from_query_string("t", "limit=1&limit=2"),
+            Err(SqlzError::InvalidValue(_))
+        ));
+        assert!(matches!(
+            Select::from_query_string("t", "select=c1"),
+            Err(SqlzError::Unsupported(_))
+        ));
+
+        let mut select = Select::from_query_string("t", "cols=c1").unwrap();
+        select.alias = Some("a".to_owned());
+        assert!(matches!(
+            select.to_query_string(),
+            Err(SqlzError::Unsupported(_))
+        ));
+    }
+}